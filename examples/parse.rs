@@ -8,9 +8,21 @@ fn main() {
     let file = std::fs::read_to_string(file).unwrap();
 
     let report = Report::from_str(&file).unwrap();
+    let summary = report.summary().expect("Empty report");
 
+    println!("Mean temp of the month: {:.1}", summary.mean_temp);
     println!(
-        "Mean temp of the month: {:.1}",
-        report.days.iter().map(|day| day.mean_temp).sum::<f32>() / report.days.len() as f32
+        "Highest temp: {:.1} on {}",
+        summary.high_temp.value,
+        summary.high_temp.date.unwrap()
+    );
+    println!(
+        "Lowest temp: {:.1} on {}",
+        summary.low_temp.value,
+        summary.low_temp.date.unwrap()
+    );
+    println!(
+        "Rain: {:.1} mm, {} days (>= .2 mm) {} days (>= 2 mm) {} days (>= 20 mm)",
+        summary.rain, summary.rain_days_0_2, summary.rain_days_2, summary.rain_days_20
     );
 }
@@ -1,31 +1,23 @@
-use std::str::FromStr;
+use std::collections::BTreeMap;
 
-use meteo::Report;
+use meteo::{Alignment, Archive, RollingWindow, Variable};
 use plotters::prelude::*;
+use time::Date;
 
 fn main() {
-    let inputs = std::env::args().skip(1);
-    let mut report: Option<Report> = None;
-    for input in inputs {
-        let r = std::fs::read_to_string(&input).unwrap();
-
-        let r = match Report::from_str(&r) {
-            Ok(r) => r,
-            Err(e) => {
-                eprintln!("Error while parsing report {input}:\n{e}");
-                continue;
-            }
-        };
-        match report {
-            Some(ref mut report) => report.merge(r).unwrap(),
-            None => report = Some(r),
-        };
+    let dir = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| String::from("original-reports"));
+    let archive = Archive::load(&dir).unwrap();
+    for (path, e) in archive.failures() {
+        eprintln!("Error while loading report {}:\n{e}", path.display());
     }
-    let report = report.expect("No valid reports inputted");
-    let output = format!("0.png");
+    let report = archive.merged().expect("No valid reports inputted");
+    let output = "0.png";
 
-    let first_date = report.first_date();
-    let last_date = report.last_date();
+    let first_date = report.first_date().unwrap();
+    let last_date = report.last_date().unwrap();
+    let (min, max) = report.temperature_range().unwrap();
 
     let root = BitMapBackend::new(&output, (1920, 1080)).into_drawing_area();
     root.fill(&WHITE).unwrap();
@@ -50,7 +42,7 @@ fn main() {
                     last_date.day() as u32,
                 )
                 .unwrap(),
-            report.temperature_range(),
+            min..max,
         )
         .unwrap();
 
@@ -65,7 +57,7 @@ fn main() {
                         day.date.month() as u32,
                         day.date.day() as u32,
                     )
-                    .expect(&format!("chrono is a piece of shit {:?}", day.date)),
+                    .unwrap_or_else(|| panic!("chrono is a piece of shit {:?}", day.date)),
                     day.mean_temp,
                 )
             }),
@@ -83,7 +75,7 @@ fn main() {
                         day.date.month() as u32,
                         day.date.day() as u32,
                     )
-                    .expect(&format!("chrono is a piece of shit {:?}", day.date)),
+                    .unwrap_or_else(|| panic!("chrono is a piece of shit {:?}", day.date)),
                     day.high_temp,
                 )
             }),
@@ -101,7 +93,7 @@ fn main() {
                         day.date.month() as u32,
                         day.date.day() as u32,
                     )
-                    .expect(&format!("chrono is a piece of shit {:?}", day.date)),
+                    .unwrap_or_else(|| panic!("chrono is a piece of shit {:?}", day.date)),
                     day.low_temp,
                 )
             }),
@@ -111,6 +103,19 @@ fn main() {
         .label("Température minimale")
         .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE));
 
+    let smoothed = RollingWindow::new(30)
+        .alignment(Alignment::Centered)
+        .mean(Variable::MeanTemp, &report.days);
+    chart
+        .draw_series(
+            runs(&smoothed)
+                .into_iter()
+                .map(|run| PathElement::new(run, BLACK.stroke_width(3))),
+        )
+        .unwrap()
+        .label("Température moyenne sur 30 jours")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLACK));
+
     chart
         .configure_series_labels()
         .background_style(WHITE.mix(0.8))
@@ -120,3 +125,28 @@ fn main() {
 
     root.present().unwrap();
 }
+
+/// The days of `smoothed` with a value, split on the missing ones so the line
+/// doesn't cross the gaps.
+fn runs(smoothed: &BTreeMap<Date, Option<f32>>) -> Vec<Vec<(chrono::NaiveDate, f32)>> {
+    let smoothed: Vec<(&Date, &Option<f32>)> = smoothed.iter().collect();
+    smoothed
+        .split(|(_, value)| value.is_none())
+        .filter(|run| !run.is_empty())
+        .map(|run| {
+            run.iter()
+                .map(|(date, value)| {
+                    (
+                        chrono::NaiveDate::from_ymd_opt(
+                            date.year(),
+                            date.month() as u32,
+                            date.day() as u32,
+                        )
+                        .unwrap_or_else(|| panic!("chrono is a piece of shit {:?}", date)),
+                        value.unwrap(),
+                    )
+                })
+                .collect()
+        })
+        .collect()
+}
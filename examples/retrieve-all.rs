@@ -1,8 +1,3 @@
-use std::str::FromStr;
-
-use logos::Logos;
-use time::{Date, Month, OffsetDateTime, PrimitiveDateTime};
-
 fn main() {
     let base_url = "http://meteo.lyc-chamson-levigan.ac-montpellier.fr/meteo/releve/fichiersbrut/sauvegardes/fichiersMensuels";
 
@@ -0,0 +1,125 @@
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use time::Date;
+
+use crate::{
+    aggregate, normals::normal_aggregation, same_day_in_year, Aggregation, Archive, Day, Normal,
+    Normals, Report, Variable,
+};
+
+/// How a value compares to its reference distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Anomaly {
+    pub variable: Variable,
+    pub value: f32,
+    /// The mean of the reference distribution.
+    pub normal: f32,
+    /// `value - normal`, the anomaly of the temperatures.
+    pub difference: f32,
+    /// `value` as a percentage of `normal`, the anomaly of the summed variables like the rain.
+    /// `None` for the other variables or if the normal is zero.
+    pub percent_of_normal: Option<f32>,
+    /// Number of standard deviations from the normal, `None` if the deviation is zero.
+    pub z_score: Option<f32>,
+    /// Percentage of the reference values below `value`, the equal ones counting for half.
+    pub percentile: f32,
+    /// `false` if the normal doesn't have enough years of data.
+    pub valid: bool,
+}
+
+impl Anomaly {
+    pub fn new(variable: Variable, value: f32, normal: &Normal) -> Self {
+        let mean = normal.stats.mean;
+        let percent_of_normal = (variable.aggregation() == Aggregation::Sum && mean != 0.0)
+            .then(|| value * 100.0 / mean);
+        let z_score = (normal.stats.std_dev != 0.0).then(|| (value - mean) / normal.stats.std_dev);
+        let below = normal.values.iter().filter(|v| **v < value).count();
+        let equal = normal.values.iter().filter(|v| **v == value).count();
+
+        Self {
+            variable,
+            value,
+            normal: mean,
+            difference: value - mean,
+            percent_of_normal,
+            z_score,
+            percentile: (below as f32 + equal as f32 / 2.0) * 100.0 / normal.values.len() as f32,
+            valid: normal.valid,
+        }
+    }
+}
+
+impl Normals {
+    /// Anomalies of a day against the daily normals.
+    pub fn day_anomalies(&self, day: &Day) -> BTreeMap<Variable, Anomaly> {
+        Variable::ALL
+            .into_iter()
+            .filter_map(|variable| {
+                let normal = self.daily(variable, day.date)?;
+                Some((variable, Anomaly::new(variable, day.get(variable)?, normal)))
+            })
+            .collect()
+    }
+
+    /// Anomalies of a report against the monthly normals of its month.
+    /// An incomplete month will have lower totals, check its [`Report::gaps`].
+    pub fn month_anomalies(&self, report: &Report) -> BTreeMap<Variable, Anomaly> {
+        let days: Vec<&Day> = report.days.iter().collect();
+        Variable::ALL
+            .into_iter()
+            .filter_map(|variable| {
+                let normal = self.monthly(variable, report.metadata.date.month())?;
+                let value = aggregate(&days, variable, normal_aggregation(variable))?.value;
+                Some((variable, Anomaly::new(variable, value, normal)))
+            })
+            .collect()
+    }
+
+    /// Anomalies of an arbitrary period against the same period of every year
+    /// of the reference, like a season or a couple of weeks.
+    /// The years covered at less than [`NormalsOptions::min_month_coverage`](crate::NormalsOptions)
+    /// are ignored. The period itself isn't checked and an incomplete period
+    /// will have lower totals, check its [`Archive::gaps`].
+    pub fn period_anomalies(
+        &self,
+        archive: &Archive,
+        range: RangeInclusive<Date>,
+    ) -> BTreeMap<Variable, Anomaly> {
+        let days_in = |range: &RangeInclusive<Date>| -> Vec<&Day> {
+            archive
+                .days()
+                .filter(|day| range.contains(&day.date))
+                .collect()
+        };
+        let current = days_in(&range);
+
+        let expected = (*range.end() - *range.start()).whole_days() as f32 + 1.0;
+        let references: Vec<Vec<&Day>> = self
+            .years
+            .clone()
+            .map(|year| year - range.start().year())
+            .map(|shift| {
+                same_day_in_year(*range.start(), range.start().year() + shift)
+                    ..=same_day_in_year(*range.end(), range.end().year() + shift)
+            })
+            .map(|range| days_in(&range))
+            .filter(|days| days.len() as f32 * 100.0 / expected >= self.options.min_month_coverage)
+            .collect();
+
+        Variable::ALL
+            .into_iter()
+            .filter(|variable| *variable != Variable::WindDirection)
+            .filter_map(|variable| {
+                let aggregation = normal_aggregation(variable);
+                let value = aggregate(&current, variable, aggregation)?.value;
+                let values: Vec<f32> = references
+                    .iter()
+                    .filter_map(|days| Some(aggregate(days, variable, aggregation)?.value))
+                    .collect();
+                let years = values.len();
+                let normal = Normal::new(values, years, self.options.min_years)?;
+                Some((variable, Anomaly::new(variable, value, &normal)))
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+
+use thiserror::Error;
+use time::{Date, Month};
+
+use crate::{Day, ParseError, Report};
+
+/// A collection of monthly reports, typically loaded from a directory of
+/// `YYYY_MM.txt` files such as `original-reports/`.
+#[derive(Debug, Default)]
+pub struct Archive {
+    // Indexed by the first day of the month of the report
+    reports: BTreeMap<Date, Report>,
+    failures: Vec<(PathBuf, ArchiveError)>,
+}
+
+#[derive(Debug, Error)]
+pub enum ArchiveError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error("Bad file name, expecting `YYYY_MM.txt`")]
+    BadFileName,
+    #[error("Conflicting reports for {0}")]
+    Duplicate(Date),
+}
+
+impl Archive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load every `.txt` file of the directory.
+    /// The month of a report is taken from its header and not from its file
+    /// name, some files of the station are saved under the wrong month.
+    /// Files that can't be read or parsed don't stop the loading, they're
+    /// recorded and can be retrieved with [`Archive::failures`].
+    pub fn load(dir: impl AsRef<Path>) -> std::io::Result<Self> {
+        let mut paths = Vec::new();
+        for entry in std::fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|ext| ext == "txt") {
+                paths.push(path);
+            }
+        }
+        paths.sort();
+
+        let mut archive = Self::new();
+        for path in paths {
+            if let Err(e) = archive.load_file(&path) {
+                archive.failures.push((path, e));
+            }
+        }
+
+        Ok(archive)
+    }
+
+    fn load_file(&mut self, path: &Path) -> Result<(), ArchiveError> {
+        date_from_file_name(path).ok_or(ArchiveError::BadFileName)?;
+        let content = std::fs::read_to_string(path)?;
+        let report = Report::from_str(&content)?;
+        self.insert(report)
+    }
+
+    /// Insert a report, fails if there is already a different report for the same month.
+    /// An identical copy, like a file saved under the wrong month, is ignored.
+    pub fn insert(&mut self, report: Report) -> Result<(), ArchiveError> {
+        let date = report.metadata.date;
+        if let Some(existing) = self.reports.get(&date) {
+            return if *existing == report {
+                Ok(())
+            } else {
+                Err(ArchiveError::Duplicate(date))
+            };
+        }
+        self.reports.insert(date, report);
+        Ok(())
+    }
+
+    /// The files that couldn't be loaded and why.
+    pub fn failures(&self) -> &[(PathBuf, ArchiveError)] {
+        &self.failures
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.reports.is_empty()
+    }
+
+    /// Number of monthly reports.
+    pub fn len(&self) -> usize {
+        self.reports.len()
+    }
+
+    /// Iterate over all the reports, sorted by month.
+    pub fn reports(&self) -> impl DoubleEndedIterator<Item = &Report> + Clone {
+        self.reports.values()
+    }
+
+    /// Iterate over all the days, sorted by date.
+    pub fn days(&self) -> impl DoubleEndedIterator<Item = &Day> + Clone {
+        self.reports.values().flat_map(|report| report.days.iter())
+    }
+
+    pub fn month(&self, year: i32, month: Month) -> Option<&Report> {
+        let date = Date::from_calendar_date(year, month, 1).ok()?;
+        self.reports.get(&date)
+    }
+
+    pub fn day(&self, date: Date) -> Option<&Day> {
+        let report = self.month(date.year(), date.month())?;
+        report
+            .days
+            .binary_search_by_key(&date, |day| day.date)
+            .ok()
+            .map(|idx| &report.days[idx])
+    }
+
+    /// Concatenate all the reports into a single one.
+    pub fn merged(&self) -> Option<Report> {
+        let mut reports = self.reports.values();
+        let mut merged = reports.next()?.clone();
+        for report in reports {
+            merged.days.extend(report.days.iter().cloned());
+        }
+        Some(merged)
+    }
+}
+
+fn date_from_file_name(path: &Path) -> Option<Date> {
+    let stem = path.file_stem()?.to_str()?;
+    let (year, month) = stem.split_once('_')?;
+    if year.len() != 4 || month.len() != 2 {
+        return None;
+    }
+    let month = Month::try_from(month.parse::<u8>().ok()?).ok()?;
+    Date::from_calendar_date(year.parse().ok()?, month, 1).ok()
+}
@@ -0,0 +1,286 @@
+use std::{fmt, ops::RangeInclusive};
+
+use time::Date;
+
+use crate::{
+    aggregate, same_day_in_year, Aggregate, Aggregation, Archive, Coverage, Day, Gaps, Variable,
+};
+
+/// A line of a [`PeriodComparison`].
+///
+/// The day counts use the thresholds of [`Summary`](crate::Summary).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Statistic {
+    /// The variable with its default aggregation, see [`Variable::aggregation`].
+    Variable(Variable),
+    /// The highest daily rain.
+    WettestDay,
+    /// Number of days with at least this much rain in mm.
+    RainDays(f32),
+    /// Number of days with a low of 0 ºC or below.
+    FrostDays,
+}
+
+impl Statistic {
+    pub const ALL: [Statistic; 14] = [
+        Statistic::Variable(Variable::MeanTemp),
+        Statistic::Variable(Variable::HighTemp),
+        Statistic::Variable(Variable::LowTemp),
+        Statistic::Variable(Variable::HeatDegDays),
+        Statistic::Variable(Variable::CoolDegDays),
+        Statistic::Variable(Variable::Rain),
+        Statistic::Variable(Variable::AvgWindSpeed),
+        Statistic::Variable(Variable::HighWindSpeed),
+        Statistic::Variable(Variable::WindDirection),
+        Statistic::WettestDay,
+        Statistic::RainDays(0.2),
+        Statistic::RainDays(2.0),
+        Statistic::RainDays(20.0),
+        Statistic::FrostDays,
+    ];
+
+    /// `None` if no day has a value.
+    pub fn compute(&self, days: &[&Day]) -> Option<Aggregate> {
+        let count = |f: &dyn Fn(&Day) -> bool| {
+            Some(Aggregate {
+                value: days.iter().filter(|day| f(day)).count() as f32,
+                count: days.len(),
+                date: None,
+                time: None,
+            })
+        };
+        match self {
+            Statistic::Variable(variable) => aggregate(days, *variable, variable.aggregation()),
+            Statistic::WettestDay => aggregate(days, Variable::Rain, Aggregation::Max),
+            Statistic::RainDays(threshold) => count(&|day| day.rain >= *threshold),
+            Statistic::FrostDays => count(&|day| day.low_temp <= 0.0),
+        }
+    }
+
+    /// If the statistic grows with the number of days, like a total of rain.
+    pub fn is_cumulative(&self) -> bool {
+        match self {
+            Statistic::Variable(variable) => variable.aggregation() == Aggregation::Sum,
+            Statistic::WettestDay => false,
+            Statistic::RainDays(_) | Statistic::FrostDays => true,
+        }
+    }
+
+    /// If a difference or a rank of the statistic makes sense.
+    fn is_ordered(&self) -> bool {
+        *self != Statistic::Variable(Variable::WindDirection)
+    }
+}
+
+impl fmt::Display for Statistic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Statistic::Variable(variable) => write!(f, "{variable} ({})", variable.unit()),
+            Statistic::WettestDay => write!(f, "wettest day ({})", Variable::Rain.unit()),
+            Statistic::RainDays(threshold) => write!(f, "rain days (≥ {threshold} mm)"),
+            Statistic::FrostDays => f.write_str("frost days"),
+        }
+    }
+}
+
+/// A statistic over both periods.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComparisonRow {
+    pub statistic: Statistic,
+    pub first: Option<Aggregate>,
+    /// Scaled to the present days of the first period if the statistic is cumulative.
+    pub second: Option<Aggregate>,
+    /// `first - second`
+    pub difference: Option<f32>,
+    /// Rank of the first period, 1 being the highest, among itself and the
+    /// same calendar period of every year of the second period, and the
+    /// number of periods ranked. The cumulative statistics are scaled like
+    /// [`ComparisonRow::second`].
+    pub rank: Option<(usize, usize)>,
+}
+
+/// Two periods side by side, like the winter 2023/24 against the winter
+/// 2022/23 or 2023 against 2006 to 2022.
+///
+/// The second period can be longer than the first: its cumulative
+/// statistics are scaled to the present days of the first period so they
+/// stay comparable, and the first period is ranked among the same calendar
+/// period of every year within the second period.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PeriodComparison {
+    pub first: RangeInclusive<Date>,
+    pub second: RangeInclusive<Date>,
+    pub first_coverage: Coverage,
+    pub second_coverage: Coverage,
+    pub rows: Vec<ComparisonRow>,
+}
+
+impl PeriodComparison {
+    /// The periods used in the ranking must be covered at 90%.
+    pub fn new<'a>(
+        days: impl IntoIterator<Item = &'a Day>,
+        first: RangeInclusive<Date>,
+        second: RangeInclusive<Date>,
+    ) -> Self {
+        let mut days: Vec<&Day> = days.into_iter().collect();
+        days.sort_by_key(|day| day.date);
+        days.dedup_by_key(|day| day.date);
+        let within = |range: &RangeInclusive<Date>| -> Vec<&Day> {
+            days.iter()
+                .copied()
+                .filter(|day| range.contains(&day.date))
+                .collect()
+        };
+
+        let first_days = within(&first);
+        let second_days = within(&second);
+        let first_coverage = Gaps::new(first_days.iter().copied(), first.clone()).total;
+        let second_coverage = Gaps::new(second_days.iter().copied(), second.clone()).total;
+        let scale = first_days.len() as f32 / second_days.len().max(1) as f32;
+
+        // The first period moved to every year it fits in the second period
+        let shifted: Vec<Vec<&Day>> = (second.start().year() - first.start().year()
+            ..=second.end().year() - first.end().year())
+            .filter(|offset| *offset != 0)
+            .filter_map(|offset| {
+                let range = same_day_in_year(*first.start(), first.start().year() + offset)
+                    ..=same_day_in_year(*first.end(), first.end().year() + offset);
+                if range.start() < second.start() || range.end() > second.end() {
+                    return None;
+                }
+                let days = within(&range);
+                let coverage = Gaps::new(days.iter().copied(), range).total;
+                (coverage.percentage() >= 90.0).then_some(days)
+            })
+            .collect();
+
+        let rows = Statistic::ALL
+            .into_iter()
+            .map(|statistic| {
+                let first = statistic.compute(&first_days);
+                let second = statistic.compute(&second_days).map(|mut second| {
+                    if statistic.is_cumulative() {
+                        second.value *= scale;
+                    }
+                    second
+                });
+                let ordered = statistic.is_ordered();
+                let rank = first
+                    .filter(|_| ordered && !shifted.is_empty())
+                    .map(|first| {
+                        let others: Vec<f32> = shifted
+                            .iter()
+                            .filter_map(|days| {
+                                let value = statistic.compute(days)?.value;
+                                Some(if statistic.is_cumulative() {
+                                    value * first_days.len() as f32 / days.len() as f32
+                                } else {
+                                    value
+                                })
+                            })
+                            .collect();
+                        (
+                            others.iter().filter(|other| **other > first.value).count() + 1,
+                            others.len() + 1,
+                        )
+                    });
+
+                ComparisonRow {
+                    statistic,
+                    first,
+                    second,
+                    difference: first
+                        .zip(second)
+                        .filter(|_| ordered)
+                        .map(|(first, second)| first.value - second.value),
+                    rank,
+                }
+            })
+            .collect();
+
+        Self {
+            first,
+            second,
+            first_coverage,
+            second_coverage,
+            rows,
+        }
+    }
+
+    pub fn row(&self, statistic: Statistic) -> Option<&ComparisonRow> {
+        self.rows.iter().find(|row| row.statistic == statistic)
+    }
+}
+
+impl Archive {
+    /// See [`PeriodComparison::new`].
+    pub fn compare(
+        &self,
+        first: RangeInclusive<Date>,
+        second: RangeInclusive<Date>,
+    ) -> PeriodComparison {
+        PeriodComparison::new(self.days(), first, second)
+    }
+}
+
+impl fmt::Display for PeriodComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let range = |range: &RangeInclusive<Date>| format!("{} to {}", range.start(), range.end());
+        let cell = |aggregate: Option<Aggregate>| match aggregate {
+            Some(Aggregate {
+                value,
+                date: Some(date),
+                ..
+            }) => format!("{value:.1} on {date}"),
+            Some(aggregate) => format!("{:.1}", aggregate.value),
+            None => String::from("---"),
+        };
+        // The scaled cells are marked by an asterisk
+        let scaled = self.first_coverage.present != self.second_coverage.present;
+
+        writeln!(
+            f,
+            "{:<24}{:>28}{:>28}{:>12}{:>8}",
+            "",
+            range(&self.first),
+            range(&self.second),
+            "difference",
+            "rank"
+        )?;
+        writeln!(
+            f,
+            "{:<24}{:>28}{:>28}",
+            "coverage",
+            format!("{:.1}%", self.first_coverage.percentage()),
+            format!("{:.1}%", self.second_coverage.percentage()),
+        )?;
+        for row in &self.rows {
+            let marker = if scaled && row.statistic.is_cumulative() && row.second.is_some() {
+                '*'
+            } else {
+                ' '
+            };
+            writeln!(
+                f,
+                "{:<24}{:>28}{:>27}{marker}{:>12}{:>8}",
+                row.statistic.to_string(),
+                cell(row.first),
+                cell(row.second),
+                row.difference
+                    .map_or(String::from("---"), |difference| format!(
+                        "{difference:+.1}"
+                    )),
+                row.rank
+                    .map_or(String::from("---"), |(rank, of)| format!("{rank}/{of}")),
+            )?;
+        }
+        if scaled {
+            writeln!(
+                f,
+                "* scaled from the {} days of the second period to the {} days of the first",
+                self.second_coverage.present, self.first_coverage.present
+            )?;
+        }
+        Ok(())
+    }
+}
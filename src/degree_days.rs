@@ -0,0 +1,180 @@
+use std::f32::consts::PI;
+
+use crate::Day;
+
+/// How the temperature curve of a day is estimated from its high, low and mean.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DegreeDayMethod {
+    /// Use the mean temperature of the day computed by the station.
+    Mean,
+    /// Use the average of the high and the low.
+    #[default]
+    Average,
+    /// Like [`DegreeDayMethod::Average`] but the high (for the heating) or
+    /// the low (for the cooling) is first clamped to the base.
+    ModifiedAverage,
+    /// Consider the temperature follows a sine between the low and the high.
+    SingleSine,
+    /// The piecewise approximation of the UK Met Office.
+    MetOffice,
+}
+
+/// Compute degree days relative to a base temperature.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DegreeDays {
+    pub base: f32,
+    pub method: DegreeDayMethod,
+}
+
+/// The degree days of the station compared to the computed ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct LoggerComparison {
+    pub days: usize,
+    pub heating: f32,
+    pub logged_heating: f32,
+    pub cooling: f32,
+    pub logged_cooling: f32,
+    /// Mean over the days of the absolute difference with the logger.
+    pub heating_mean_abs_error: f32,
+    /// Mean over the days of the absolute difference with the logger.
+    pub cooling_mean_abs_error: f32,
+}
+
+impl DegreeDays {
+    /// The base used by the station for both the heat and cool degree days.
+    pub const LOGGER_BASE: f32 = 18.3;
+    /// Base commonly used for the heating degree days in the UK.
+    pub const UK_BASE: f32 = 15.5;
+    /// Base commonly used for the growing degree days of crops.
+    pub const GROWING_BASE: f32 = 10.0;
+
+    pub fn new(base: f32, method: DegreeDayMethod) -> Self {
+        Self { base, method }
+    }
+
+    /// Degrees below the base.
+    pub fn heating(&self, day: &Day) -> f32 {
+        self.heating_from(day.high_temp, day.low_temp, day.mean_temp)
+    }
+
+    /// Degrees above the base.
+    pub fn cooling(&self, day: &Day) -> f32 {
+        self.cooling_from(day.high_temp, day.low_temp, day.mean_temp)
+    }
+
+    /// Degrees above the base with the temperatures capped to `cap` if any, usually 30 ºC.
+    pub fn growing(&self, day: &Day, cap: Option<f32>) -> f32 {
+        let cap = cap.unwrap_or(f32::INFINITY);
+        self.cooling_from(
+            day.high_temp.min(cap),
+            day.low_temp.min(cap),
+            day.mean_temp.min(cap),
+        )
+    }
+
+    pub fn heating_total<'a>(&self, days: impl IntoIterator<Item = &'a Day>) -> f32 {
+        days.into_iter().map(|day| self.heating(day)).sum()
+    }
+
+    pub fn cooling_total<'a>(&self, days: impl IntoIterator<Item = &'a Day>) -> f32 {
+        days.into_iter().map(|day| self.cooling(day)).sum()
+    }
+
+    pub fn growing_total<'a>(
+        &self,
+        days: impl IntoIterator<Item = &'a Day>,
+        cap: Option<f32>,
+    ) -> f32 {
+        days.into_iter().map(|day| self.growing(day, cap)).sum()
+    }
+
+    /// Compare with the heat and cool degree days of the reports.
+    /// It only makes sense with the base of the station, see [`DegreeDays::LOGGER_BASE`].
+    pub fn compare_with_logger<'a>(
+        &self,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> LoggerComparison {
+        let mut comparison = LoggerComparison::default();
+        for day in days {
+            let heating = self.heating(day);
+            let cooling = self.cooling(day);
+            comparison.days += 1;
+            comparison.heating += heating;
+            comparison.logged_heating += day.heat_deg_days;
+            comparison.cooling += cooling;
+            comparison.logged_cooling += day.cool_deg_days;
+            comparison.heating_mean_abs_error += (heating - day.heat_deg_days).abs();
+            comparison.cooling_mean_abs_error += (cooling - day.cool_deg_days).abs();
+        }
+        if comparison.days != 0 {
+            comparison.heating_mean_abs_error /= comparison.days as f32;
+            comparison.cooling_mean_abs_error /= comparison.days as f32;
+        }
+        comparison
+    }
+
+    fn heating_from(&self, high: f32, low: f32, mean: f32) -> f32 {
+        let base = self.base;
+        match self.method {
+            DegreeDayMethod::Mean => (base - mean).max(0.0),
+            DegreeDayMethod::Average => (base - (high + low) / 2.0).max(0.0),
+            DegreeDayMethod::ModifiedAverage => (base - (high.min(base) + low) / 2.0).max(0.0),
+            // The heating is what's left of the cooling once the mean is removed
+            DegreeDayMethod::SingleSine => {
+                (single_sine(high, low, base) - ((high + low) / 2.0 - base)).max(0.0)
+            }
+            DegreeDayMethod::MetOffice => {
+                if high <= base {
+                    base - (high + low) / 2.0
+                } else if low >= base {
+                    0.0
+                } else if high - base < base - low {
+                    (base - low) / 2.0 - (high - base) / 4.0
+                } else {
+                    (base - low) / 4.0
+                }
+            }
+        }
+    }
+
+    fn cooling_from(&self, high: f32, low: f32, mean: f32) -> f32 {
+        let base = self.base;
+        match self.method {
+            DegreeDayMethod::Mean => (mean - base).max(0.0),
+            DegreeDayMethod::Average => ((high + low) / 2.0 - base).max(0.0),
+            DegreeDayMethod::ModifiedAverage => ((high + low.max(base)) / 2.0 - base).max(0.0),
+            DegreeDayMethod::SingleSine => single_sine(high, low, base),
+            DegreeDayMethod::MetOffice => {
+                if low >= base {
+                    (high + low) / 2.0 - base
+                } else if high <= base {
+                    0.0
+                } else if high - base > base - low {
+                    (high - base) / 2.0 - (base - low) / 4.0
+                } else {
+                    (high - base) / 4.0
+                }
+            }
+        }
+    }
+}
+
+impl Default for DegreeDays {
+    fn default() -> Self {
+        Self::new(Self::LOGGER_BASE, DegreeDayMethod::default())
+    }
+}
+
+/// Area above `base` of a sine going from `low` to `high` over a day.
+fn single_sine(high: f32, low: f32, base: f32) -> f32 {
+    let mean = (high + low) / 2.0;
+    if low >= base {
+        mean - base
+    } else if high <= base {
+        0.0
+    } else {
+        let amplitude = (high - low) / 2.0;
+        let theta = ((base - mean) / amplitude).asin();
+        ((mean - base) * (PI / 2.0 - theta) + amplitude * theta.cos()) / PI
+    }
+}
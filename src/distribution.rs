@@ -0,0 +1,330 @@
+use crate::stats::{incomplete_gamma, ln_gamma};
+
+/// A continuous probability distribution fitted on a sample.
+pub trait Distribution: Sized {
+    /// Fit the distribution on the values, `None` if they aren't enough or
+    /// don't fit the support of the distribution.
+    fn fit(values: &[f64]) -> Option<Self>;
+
+    /// Probability of a value lower or equal to `x`.
+    fn cdf(&self, x: f64) -> f64;
+
+    /// Inverse of [`Distribution::cdf`], `p` is between 0 and 1.
+    fn quantile(&self, p: f64) -> f64;
+}
+
+/// The two parameters gamma distribution, defined for `x > 0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gamma {
+    pub shape: f64,
+    pub scale: f64,
+}
+
+impl Distribution for Gamma {
+    /// Maximum likelihood estimation with the approximation of Thom (1958).
+    /// Needs at least 3 positive values, not all equal.
+    fn fit(values: &[f64]) -> Option<Self> {
+        if values.len() < 3 || values.iter().any(|value| *value <= 0.0) {
+            return None;
+        }
+        let n = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / n;
+        let a = mean.ln() - values.iter().map(|value| value.ln()).sum::<f64>() / n;
+        if a <= 0.0 || !a.is_finite() {
+            return None;
+        }
+        let shape = (1.0 + (1.0 + 4.0 * a / 3.0).sqrt()) / (4.0 * a);
+        Some(Self {
+            shape,
+            scale: mean / shape,
+        })
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        incomplete_gamma(self.shape, x / self.scale)
+    }
+
+    /// Found by bisection.
+    fn quantile(&self, p: f64) -> f64 {
+        if p <= 0.0 {
+            return 0.0;
+        }
+        if p >= 1.0 {
+            return f64::INFINITY;
+        }
+        let mut high = self.shape * self.scale;
+        while self.cdf(high) < p {
+            high *= 2.0;
+        }
+        let mut low = 0.0;
+        for _ in 0..100 {
+            let mid = (low + high) / 2.0;
+            if self.cdf(mid) < p {
+                low = mid;
+            } else {
+                high = mid;
+            }
+        }
+        (low + high) / 2.0
+    }
+}
+
+/// A distribution with a probability mass on zero, like the rain where the
+/// dry periods can't be fitted by a distribution of positive values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ZeroInflated<D> {
+    /// Probability of a zero, between 0 and 1.
+    pub zero: f64,
+    /// Distribution of the positive values.
+    pub positive: D,
+}
+
+impl<D: Distribution> Distribution for ZeroInflated<D> {
+    /// The negative values are counted as zeros.
+    fn fit(values: &[f64]) -> Option<Self> {
+        let positive: Vec<f64> = values
+            .iter()
+            .copied()
+            .filter(|value| *value > 0.0)
+            .collect();
+        Some(Self {
+            zero: (values.len() - positive.len()) as f64 / values.len() as f64,
+            positive: D::fit(&positive)?,
+        })
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        if x < 0.0 {
+            0.0
+        } else if x == 0.0 {
+            self.zero
+        } else {
+            self.zero + (1.0 - self.zero) * self.positive.cdf(x)
+        }
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        if p <= self.zero {
+            0.0
+        } else {
+            self.positive.quantile((p - self.zero) / (1.0 - self.zero))
+        }
+    }
+}
+
+/// The Gumbel distribution, the type I extreme value distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gumbel {
+    pub location: f64,
+    pub scale: f64,
+}
+
+impl Distribution for Gumbel {
+    /// Fitted by the L-moments, needs at least 3 values, not all equal.
+    fn fit(values: &[f64]) -> Option<Self> {
+        let [l1, l2, _] = l_moments(values)?;
+        let scale = l2 / std::f64::consts::LN_2;
+        Some(Self {
+            location: l1 - EULER * scale,
+            scale,
+        })
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        (-(-(x - self.location) / self.scale).exp()).exp()
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        self.location - self.scale * (-p.ln()).ln()
+    }
+}
+
+/// The generalized extreme value distribution.
+///
+/// The shape follows the sign convention of Hosking: a positive shape bounds
+/// the upper tail, a negative one gives a heavy upper tail and zero is the
+/// Gumbel distribution.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Gev {
+    pub location: f64,
+    pub scale: f64,
+    pub shape: f64,
+}
+
+impl From<Gumbel> for Gev {
+    fn from(gumbel: Gumbel) -> Self {
+        Self {
+            location: gumbel.location,
+            scale: gumbel.scale,
+            shape: 0.0,
+        }
+    }
+}
+
+impl Distribution for Gev {
+    /// Fitted by the L-moments with the approximation of Hosking et al. (1985),
+    /// needs at least 3 values, not all equal.
+    fn fit(values: &[f64]) -> Option<Self> {
+        let [l1, l2, l3] = l_moments(values)?;
+        let c = 2.0 / (3.0 + l3 / l2) - std::f64::consts::LN_2 / 3f64.ln();
+        let shape = 7.8590 * c + 2.9554 * c * c;
+        if shape.abs() < 1e-6 {
+            return Gumbel::fit(values).map(Self::from);
+        }
+        // The mean is infinite below -1
+        if shape <= -1.0 {
+            return None;
+        }
+        let gamma = ln_gamma(1.0 + shape).exp();
+        let scale = l2 * shape / ((1.0 - 2f64.powf(-shape)) * gamma);
+        Some(Self {
+            location: l1 - scale * (1.0 - gamma) / shape,
+            scale,
+            shape,
+        })
+    }
+
+    fn cdf(&self, x: f64) -> f64 {
+        let y = (x - self.location) / self.scale;
+        if self.shape == 0.0 {
+            return (-(-y).exp()).exp();
+        }
+        let base = 1.0 - self.shape * y;
+        if base <= 0.0 {
+            // Outside of the support
+            return if self.shape > 0.0 { 1.0 } else { 0.0 };
+        }
+        (-base.powf(1.0 / self.shape)).exp()
+    }
+
+    fn quantile(&self, p: f64) -> f64 {
+        let y = -p.ln();
+        if self.shape == 0.0 {
+            self.location - self.scale * y.ln()
+        } else {
+            self.location + self.scale * (1.0 - y.powf(self.shape)) / self.shape
+        }
+    }
+}
+
+/// The Euler-Mascheroni constant.
+const EULER: f64 = 0.5772156649015329;
+
+/// The first three L-moments, `None` with less than 3 values or if they are all equal.
+fn l_moments(values: &[f64]) -> Option<[f64; 3]> {
+    let n = values.len();
+    if n < 3 {
+        return None;
+    }
+    let mut sorted = values.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let n = n as f64;
+    let (mut b0, mut b1, mut b2) = (0.0, 0.0, 0.0);
+    for (i, value) in sorted.iter().enumerate() {
+        let i = i as f64;
+        b0 += value;
+        b1 += value * i / (n - 1.0);
+        b2 += value * i * (i - 1.0) / ((n - 1.0) * (n - 2.0));
+    }
+    let (b0, b1, b2) = (b0 / n, b1 / n, b2 / n);
+    let l2 = 2.0 * b1 - b0;
+    (l2 > 0.0).then_some([b0, l2, 6.0 * b2 - 6.0 * b1 + b0])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: f64, expected: f64, tolerance: f64) {
+        assert!(
+            (actual - expected).abs() < tolerance,
+            "{actual} isn't within {tolerance} of {expected}"
+        );
+    }
+
+    #[test]
+    fn gamma_fit_follows_thom() {
+        let gamma = Gamma::fit(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert_close(gamma.shape, 3.702724, 1e-6);
+        assert_close(gamma.scale, 0.810214, 1e-6);
+        assert!(Gamma::fit(&[2.0, 2.0, 2.0]).is_none());
+        assert!(Gamma::fit(&[0.0, 1.0, 2.0]).is_none());
+    }
+
+    #[test]
+    fn gamma_cdf_and_quantile() {
+        let gamma = Gamma {
+            shape: 2.0,
+            scale: 3.0,
+        };
+        // P(2, 1) = 1 - 2/e
+        assert_close(gamma.cdf(3.0), 0.264241, 1e-6);
+        assert_close(gamma.quantile(0.264241), 3.0, 1e-4);
+    }
+
+    #[test]
+    fn zero_inflated_gamma() {
+        let fitted = ZeroInflated::<Gamma>::fit(&[0.0, 1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert_close(fitted.zero, 1.0 / 6.0, 1e-9);
+        assert_close(fitted.cdf(0.0), 1.0 / 6.0, 1e-9);
+        assert_eq!(fitted.quantile(0.1), 0.0);
+        let p = fitted.cdf(2.5);
+        assert_close(p, 1.0 / 6.0 + 5.0 / 6.0 * fitted.positive.cdf(2.5), 1e-12);
+        assert_close(fitted.quantile(p), 2.5, 1e-4);
+    }
+
+    #[test]
+    fn l_moments_of_small_samples() {
+        assert_eq!(l_moments(&[5.0, 1.0, 3.0, 2.0, 4.0]), Some([3.0, 1.0, 0.0]));
+        let [l1, l2, l3] = l_moments(&[1.0, 2.0, 4.0, 8.0, 16.0]).unwrap();
+        assert_close(l1, 6.2, 1e-12);
+        assert_close(l2, 3.6, 1e-12);
+        assert_close(l3, 1.6, 1e-12);
+        assert!(l_moments(&[1.0, 2.0]).is_none());
+        assert!(l_moments(&[3.0, 3.0, 3.0]).is_none());
+    }
+
+    #[test]
+    fn gumbel_fit_by_l_moments() {
+        let gumbel = Gumbel::fit(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert_close(gumbel.scale, 1.0 / std::f64::consts::LN_2, 1e-12);
+        assert_close(gumbel.location, 2.167254, 1e-6);
+        let gev = Gev::from(gumbel);
+        for p in [0.1, 0.5, 0.99] {
+            assert_close(gev.quantile(p), gumbel.quantile(p), 1e-9);
+            assert_close(gumbel.cdf(gumbel.quantile(p)), p, 1e-9);
+        }
+    }
+
+    #[test]
+    fn gev_fit_by_l_moments() {
+        // t3 = 0 is lighter tailed than the Gumbel distribution and its t3 of 0.17
+        let gev = Gev::fit(&[1.0, 2.0, 3.0, 4.0, 5.0]).unwrap();
+        assert_close(gev.shape, 0.284631, 1e-6);
+
+        let gev = Gev::fit(&[1.0, 2.0, 4.0, 8.0, 16.0]).unwrap();
+        assert_close(gev.shape, -0.387714, 1e-6);
+        assert_close(gev.scale, 3.097177, 1e-6);
+        assert_close(gev.location, 2.512070, 1e-6);
+        for p in [0.1, 0.5, 0.99] {
+            assert_close(gev.cdf(gev.quantile(p)), p, 1e-9);
+        }
+    }
+
+    #[test]
+    fn gev_fit_recovers_the_parameters() {
+        let gev = Gev {
+            location: 10.0,
+            scale: 2.0,
+            shape: 0.1,
+        };
+        let n = 2000;
+        let values: Vec<f64> = (1..=n)
+            .map(|i| gev.quantile((i as f64 - 0.35) / n as f64))
+            .collect();
+        let fitted = Gev::fit(&values).unwrap();
+        assert_close(fitted.location, 10.0, 0.05);
+        assert_close(fitted.scale, 2.0, 0.05);
+        assert_close(fitted.shape, 0.1, 0.01);
+    }
+}
@@ -0,0 +1,315 @@
+use std::{collections::BTreeMap, f32::consts::PI};
+
+use crate::{percentile, Day, Season, Stats, Variable};
+
+/// The observed distribution of a variable over a set of days.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Empirical {
+    sorted: Vec<f32>,
+}
+
+/// How the values are split in a [`Histogram`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum Bins {
+    /// This many bins of the same width between the min and the max.
+    Count(usize),
+    /// Bins of this width, aligned on multiples of the width.
+    Width(f32),
+    /// The edges of the bins in increasing order, `n + 1` edges for `n` bins.
+    /// The values outside of the edges are ignored.
+    Edges(Vec<f32>),
+}
+
+/// The number of values in each bin. Every bin includes its lower edge,
+/// only the last one includes its upper edge.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    pub edges: Vec<f32>,
+    pub counts: Vec<usize>,
+}
+
+/// The five-number summary drawn by a box plot.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FiveNumber {
+    pub min: f32,
+    pub q1: f32,
+    pub median: f32,
+    pub q3: f32,
+    pub max: f32,
+}
+
+/// A gaussian kernel density estimate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Kde<'a> {
+    pub bandwidth: f32,
+    values: &'a [f32],
+}
+
+impl Empirical {
+    /// The values of `variable` of the days, the days without value are skipped.
+    pub fn new<'a>(variable: Variable, days: impl IntoIterator<Item = &'a Day>) -> Self {
+        Self::from_values(days.into_iter().filter_map(|day| day.get(variable)))
+    }
+
+    pub fn from_values(values: impl IntoIterator<Item = f32>) -> Self {
+        let mut sorted: Vec<f32> = values.into_iter().filter(|v| !v.is_nan()).collect();
+        sorted.sort_by(f32::total_cmp);
+        Self { sorted }
+    }
+
+    /// One distribution per calendar month, indexed from 1 to 12.
+    pub fn by_month<'a>(
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<u8, Self> {
+        Self::group(variable, days, |day| day.date.month() as u8)
+    }
+
+    pub fn by_season<'a>(
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<Season, Self> {
+        Self::group(variable, days, |day| Season::of(day.date.month()))
+    }
+
+    fn group<'a, K: Ord>(
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+        key: impl Fn(&Day) -> K,
+    ) -> BTreeMap<K, Self> {
+        let mut groups: BTreeMap<K, Vec<f32>> = BTreeMap::new();
+        for day in days {
+            if let Some(value) = day.get(variable) {
+                groups.entry(key(day)).or_default().push(value);
+            }
+        }
+        groups
+            .into_iter()
+            .map(|(key, values)| (key, Self::from_values(values)))
+            .collect()
+    }
+
+    /// The values in increasing order.
+    pub fn values(&self) -> &[f32] {
+        &self.sorted
+    }
+
+    pub fn len(&self) -> usize {
+        self.sorted.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.sorted.is_empty()
+    }
+
+    pub fn stats(&self) -> Option<Stats> {
+        Stats::new(&self.sorted)
+    }
+
+    /// See [`percentile`].
+    pub fn percentile(&self, p: f32) -> Option<f32> {
+        percentile(&self.sorted, p)
+    }
+
+    /// Percentage of the values lower or equal to `value`.
+    pub fn percentile_of(&self, value: f32) -> Option<f32> {
+        if self.sorted.is_empty() {
+            return None;
+        }
+        let below = self.sorted.partition_point(|v| *v <= value);
+        Some(below as f32 * 100.0 / self.sorted.len() as f32)
+    }
+
+    pub fn five_number(&self) -> Option<FiveNumber> {
+        Some(FiveNumber {
+            min: *self.sorted.first()?,
+            q1: self.percentile(25.0)?,
+            median: self.percentile(50.0)?,
+            q3: self.percentile(75.0)?,
+            max: *self.sorted.last()?,
+        })
+    }
+
+    /// Where the whiskers of a box plot end: the most extreme values within
+    /// 1.5 interquartile range of the quartiles.
+    pub fn whiskers(&self) -> Option<(f32, f32)> {
+        let five = self.five_number()?;
+        let (low, high) = (five.q1 - 1.5 * five.iqr(), five.q3 + 1.5 * five.iqr());
+        let values = self.sorted.iter().copied();
+        Some((
+            values.clone().find(|v| *v >= low)?,
+            values.rev().find(|v| *v <= high)?,
+        ))
+    }
+
+    /// `None` if there is no value or if the bins are invalid.
+    pub fn histogram(&self, bins: &Bins) -> Option<Histogram> {
+        let (min, max) = (*self.sorted.first()?, *self.sorted.last()?);
+        let edges = match bins {
+            Bins::Count(0) => return None,
+            Bins::Count(count) => {
+                // A single value still needs a bin with a width
+                let upper = if max > min { max } else { min + 1.0 };
+                let width = (upper - min) / *count as f32;
+                // The outer edges are exact so the rounding can't leave out the min or the max
+                (0..=*count)
+                    .map(|i| match i {
+                        0 => min,
+                        i if i == *count => upper,
+                        i => min + width * i as f32,
+                    })
+                    .collect()
+            }
+            Bins::Width(width) if *width <= 0.0 || !width.is_finite() => return None,
+            Bins::Width(width) => {
+                let mut first = (min / width).floor() as i64;
+                let mut last = ((max / width).floor() as i64 + 1).max(first + 1);
+                // The division can round across a multiple of the width
+                while first as f32 * width > min {
+                    first -= 1;
+                }
+                while (last as f32 * width) < max {
+                    last += 1;
+                }
+                (first..=last).map(|i| i as f32 * width).collect()
+            }
+            Bins::Edges(edges) if edges.len() < 2 || !edges.is_sorted() => return None,
+            Bins::Edges(edges) => edges.clone(),
+        };
+
+        let mut counts = vec![0; edges.len() - 1];
+        let last = edges.len() - 1;
+        for value in &self.sorted {
+            if *value < edges[0] || *value > edges[last] {
+                continue;
+            }
+            let bin = edges.partition_point(|edge| edge <= value).clamp(1, last) - 1;
+            counts[bin] += 1;
+        }
+        Some(Histogram { edges, counts })
+    }
+
+    /// With the rule of thumb of Silverman when `bandwidth` is `None`.
+    /// `None` if there is no value or the bandwidth isn't positive.
+    pub fn kde(&self, bandwidth: Option<f32>) -> Option<Kde<'_>> {
+        let bandwidth = match bandwidth {
+            Some(bandwidth) => bandwidth,
+            None => {
+                let std_dev = self.stats()?.std_dev;
+                let iqr = self.percentile(75.0)? - self.percentile(25.0)?;
+                let spread = match iqr / 1.34 {
+                    iqr if iqr > 0.0 => std_dev.min(iqr),
+                    _ => std_dev,
+                };
+                0.9 * spread * (self.sorted.len() as f32).powf(-0.2)
+            }
+        };
+        (bandwidth > 0.0 && !self.sorted.is_empty()).then_some(Kde {
+            bandwidth,
+            values: &self.sorted,
+        })
+    }
+}
+
+impl Histogram {
+    /// `(start, end, count)` of each bin.
+    pub fn bins(&self) -> impl Iterator<Item = (f32, f32, usize)> + '_ {
+        self.edges
+            .windows(2)
+            .zip(&self.counts)
+            .map(|(edges, count)| (edges[0], edges[1], *count))
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// The percentage of the values in each bin.
+    pub fn frequencies(&self) -> Vec<f32> {
+        let total = self.total().max(1) as f32;
+        self.counts
+            .iter()
+            .map(|count| *count as f32 * 100.0 / total)
+            .collect()
+    }
+}
+
+impl FiveNumber {
+    /// The interquartile range.
+    pub fn iqr(&self) -> f32 {
+        self.q3 - self.q1
+    }
+}
+
+impl Kde<'_> {
+    pub fn density(&self, x: f32) -> f32 {
+        let sum: f32 = self
+            .values
+            .iter()
+            .map(|value| (-0.5 * ((x - value) / self.bandwidth).powi(2)).exp())
+            .sum();
+        sum / (self.values.len() as f32 * self.bandwidth * (2.0 * PI).sqrt())
+    }
+
+    /// The density on `points` evenly spaced points, from three bandwidths
+    /// below the min to three bandwidths above the max.
+    pub fn curve(&self, points: usize) -> Vec<(f32, f32)> {
+        let start = self.values[0] - 3.0 * self.bandwidth;
+        let end = self.values[self.values.len() - 1] + 3.0 * self.bandwidth;
+        let step = (end - start) / points.saturating_sub(1).max(1) as f32;
+        (0..points)
+            .map(|i| {
+                let x = start + step * i as f32;
+                (x, self.density(x))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn count_bins_hold_every_value() {
+        let empirical = Empirical::from_values([0.0, 3.33]);
+        let histogram = empirical.histogram(&Bins::Count(11)).unwrap();
+        assert_eq!(histogram.edges.first(), Some(&0.0));
+        assert_eq!(histogram.edges.last(), Some(&3.33));
+        assert_eq!(histogram.total(), empirical.len());
+
+        for count in 1..50 {
+            let empirical = Empirical::from_values([-7.3, 0.1, 12.9, 21.7, 38.9]);
+            let histogram = empirical.histogram(&Bins::Count(count)).unwrap();
+            assert_eq!(histogram.counts.len(), count);
+            assert_eq!(histogram.total(), empirical.len());
+        }
+
+        let single = Empirical::from_values([4.0, 4.0]);
+        let histogram = single.histogram(&Bins::Count(2)).unwrap();
+        assert_eq!(histogram.edges, [4.0, 4.5, 5.0]);
+        assert_eq!(histogram.total(), 2);
+    }
+
+    #[test]
+    fn width_bins_hold_every_value() {
+        let empirical = Empirical::from_values([-28.7, -3.5, 12.6]);
+        let histogram = empirical.histogram(&Bins::Width(0.7)).unwrap();
+        assert!(histogram.edges[0] <= -28.7);
+        assert_eq!(histogram.total(), empirical.len());
+
+        let histogram = Empirical::from_values([0.0, 1.0, 2.0])
+            .histogram(&Bins::Width(1.0))
+            .unwrap();
+        assert_eq!(histogram.edges, [0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(histogram.counts, [1, 1, 1]);
+    }
+
+    #[test]
+    fn edges_ignore_the_values_outside() {
+        let histogram = Empirical::from_values([-1.0, 0.0, 1.0, 2.0, 3.0])
+            .histogram(&Bins::Edges(vec![0.0, 1.0, 2.0]))
+            .unwrap();
+        assert_eq!(histogram.counts, [1, 2]);
+    }
+}
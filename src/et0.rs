@@ -0,0 +1,87 @@
+use std::f64::consts::PI;
+
+use time::Date;
+
+use crate::{Day, Report};
+
+/// Solar constant in MJ m⁻² min⁻¹.
+const SOLAR_CONSTANT: f64 = 0.0820;
+
+/// Radiation received at the top of the atmosphere in MJ m⁻² day⁻¹, see the
+/// equation 21 of the FAO-56. `latitude` is in decimal degrees, negative in the south.
+pub fn extraterrestrial_radiation(latitude: f64, day_of_year: u16) -> f64 {
+    let latitude = latitude.to_radians();
+    let angle = 2.0 * PI * day_of_year as f64 / 365.0;
+    let inverse_distance = 1.0 + 0.033 * angle.cos();
+    let declination = 0.409 * (angle - 1.39).sin();
+    let sunset = (-latitude.tan() * declination.tan())
+        .clamp(-1.0, 1.0)
+        .acos();
+
+    24.0 * 60.0 / PI
+        * SOLAR_CONSTANT
+        * inverse_distance
+        * (sunset * latitude.sin() * declination.sin()
+            + latitude.cos() * declination.cos() * sunset.sin())
+}
+
+impl Day {
+    /// Reference evapotranspiration in mm with the Hargreaves-Samani equation.
+    /// `latitude` is in decimal degrees, negative in the south.
+    pub fn et0_hargreaves(&self, latitude: f64) -> f32 {
+        let radiation = extraterrestrial_radiation(latitude, self.date.ordinal());
+        // 0.408 converts the radiation from MJ m⁻² to mm of evaporated water
+        let et0 = 0.0023
+            * (self.mean_temp as f64 + 17.8)
+            * ((self.high_temp - self.low_temp).max(0.0) as f64).sqrt()
+            * radiation
+            * 0.408;
+        et0.max(0.0) as f32
+    }
+}
+
+/// The water balance of a day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WaterBalance {
+    pub date: Date,
+    pub rain: f32,
+    pub et0: f32,
+    /// `rain - et0`
+    pub balance: f32,
+    /// Sum of the balances since the first day.
+    pub cumulative_balance: f32,
+    /// Water missing since the last time the rain covered the evapotranspiration,
+    /// never negative.
+    pub cumulative_deficit: f32,
+}
+
+impl WaterBalance {
+    /// The daily water balance, the missing days are skipped.
+    pub fn compute<'a>(days: impl IntoIterator<Item = &'a Day>, latitude: f64) -> Vec<Self> {
+        let mut cumulative_balance = 0.0;
+        let mut cumulative_deficit: f32 = 0.0;
+        days.into_iter()
+            .map(|day| {
+                let et0 = day.et0_hargreaves(latitude);
+                let balance = day.rain - et0;
+                cumulative_balance += balance;
+                cumulative_deficit = (cumulative_deficit - balance).max(0.0);
+                Self {
+                    date: day.date,
+                    rain: day.rain,
+                    et0,
+                    balance,
+                    cumulative_balance,
+                    cumulative_deficit,
+                }
+            })
+            .collect()
+    }
+}
+
+impl Report {
+    /// The daily water balance at the latitude of the station.
+    pub fn water_balance(&self) -> Vec<WaterBalance> {
+        WaterBalance::compute(&self.days, self.metadata.latitude())
+    }
+}
@@ -0,0 +1,349 @@
+use std::{collections::BTreeMap, fmt, ops::RangeInclusive};
+
+use time::{Date, Month};
+
+use crate::{day_of_year, last_day_of_month, percentile, Day, Period, SpellFinder};
+
+/// The core indices of the Expert Team on Climate Change Detection and Indices.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ClimateIndex {
+    /// Frost days: number of days with a low below 0 ºC.
+    FD,
+    /// Summer days: number of days with a high above 25 ºC.
+    SU,
+    /// Icing days: number of days with a high below 0 ºC.
+    ID,
+    /// Tropical nights: number of days with a low above 20 ºC.
+    TR,
+    /// Highest high.
+    TXx,
+    /// Lowest low.
+    TNn,
+    /// Percentage of days with a high above the 90th percentile of the base period.
+    TX90p,
+    /// Percentage of days with a low below the 10th percentile of the base period.
+    TN10p,
+    /// Mean difference between the high and the low.
+    DTR,
+    /// Growing season length: days between the first span of 6 days with a
+    /// mean above 5 ºC and the first span of 6 days with a mean below 5 ºC
+    /// after the first of july. Only yearly.
+    GSL,
+    /// Highest daily rain.
+    Rx1day,
+    /// Highest rain over 5 consecutive days.
+    Rx5day,
+    /// Number of days with at least 10 mm of rain.
+    R10mm,
+    /// Number of days with at least 20 mm of rain.
+    R20mm,
+    /// Longest spell of days with less than 1 mm of rain.
+    CDD,
+    /// Longest spell of days with at least 1 mm of rain.
+    CWD,
+    /// Mean rain of the days with at least 1 mm of rain.
+    SDII,
+    /// Total rain of the days with at least 1 mm of rain.
+    PRCPTOT,
+}
+
+impl ClimateIndex {
+    pub const ALL: [ClimateIndex; 18] = [
+        ClimateIndex::FD,
+        ClimateIndex::SU,
+        ClimateIndex::ID,
+        ClimateIndex::TR,
+        ClimateIndex::TXx,
+        ClimateIndex::TNn,
+        ClimateIndex::TX90p,
+        ClimateIndex::TN10p,
+        ClimateIndex::DTR,
+        ClimateIndex::GSL,
+        ClimateIndex::Rx1day,
+        ClimateIndex::Rx5day,
+        ClimateIndex::R10mm,
+        ClimateIndex::R20mm,
+        ClimateIndex::CDD,
+        ClimateIndex::CWD,
+        ClimateIndex::SDII,
+        ClimateIndex::PRCPTOT,
+    ];
+}
+
+impl fmt::Display for ClimateIndex {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Debug::fmt(self, f)
+    }
+}
+
+/// Compute the ETCCDI indices per month or per year.
+///
+/// Following the ETCCDI rules a month with more than 3 missing days and a
+/// year with more than 15 missing days or an invalid month don't have a value.
+/// The percentiles of the base period aren't bootstrapped.
+#[derive(Debug, Clone, Default)]
+pub struct Etccdi {
+    // Indexed by the day of the year of a leap year
+    tx90: BTreeMap<u16, f32>,
+    tn10: BTreeMap<u16, f32>,
+}
+
+impl Etccdi {
+    /// Without base period, [`ClimateIndex::TX90p`] and [`ClimateIndex::TN10p`] can't be computed.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Compute the percentiles of each calendar day on a 5 days window
+    /// centered on the day, over the days of the base period.
+    pub fn with_base_period<'a>(
+        days: impl IntoIterator<Item = &'a Day>,
+        years: RangeInclusive<i32>,
+    ) -> Self {
+        let mut highs: BTreeMap<u16, Vec<f32>> = BTreeMap::new();
+        let mut lows: BTreeMap<u16, Vec<f32>> = BTreeMap::new();
+        for day in days
+            .into_iter()
+            .filter(|day| years.contains(&day.date.year()))
+        {
+            let doy = day_of_year(day.date) as i32;
+            for offset in -2..=2 {
+                let doy = (doy - 1 + offset).rem_euclid(366) as u16 + 1;
+                highs.entry(doy).or_default().push(day.high_temp);
+                lows.entry(doy).or_default().push(day.low_temp);
+            }
+        }
+        let threshold = |values: BTreeMap<u16, Vec<f32>>, p| {
+            values
+                .into_iter()
+                .filter_map(|(doy, mut values)| {
+                    values.sort_by(f32::total_cmp);
+                    Some((doy, percentile(&values, p)?))
+                })
+                .collect()
+        };
+
+        Self {
+            tx90: threshold(highs, 90.0),
+            tn10: threshold(lows, 10.0),
+        }
+    }
+
+    /// The index of each month, indexed by its first day. `None` if the month has too many missing days.
+    pub fn monthly<'a>(
+        &self,
+        index: ClimateIndex,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<Date, Option<f32>> {
+        let days = sorted(days);
+        let five_days = five_day_sums(&days);
+        self.periods(Period::Month, days)
+            .into_iter()
+            .map(|(start, days)| {
+                let end = last_day_of_month(start);
+                let value = (missing(&days, start, end) <= 3)
+                    .then(|| self.compute(index, &days, &five_days, start, end))
+                    .flatten();
+                (start, value)
+            })
+            .collect()
+    }
+
+    /// The index of each year. `None` if the year has too many missing days.
+    pub fn annual<'a>(
+        &self,
+        index: ClimateIndex,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<i32, Option<f32>> {
+        let days = sorted(days);
+        let five_days = five_day_sums(&days);
+        self.periods(Period::Year, days)
+            .into_iter()
+            .map(|(start, days)| {
+                let end = Date::from_calendar_date(start.year(), Month::December, 31).unwrap();
+                let months_valid = self
+                    .periods(Period::Month, days.iter().copied())
+                    .into_iter()
+                    .all(|(start, days)| missing(&days, start, last_day_of_month(start)) <= 3);
+                let value = (missing(&days, start, end) <= 15 && months_valid)
+                    .then(|| self.compute(index, &days, &five_days, start, end))
+                    .flatten();
+                (start.year(), value)
+            })
+            .collect()
+    }
+
+    fn periods<'a>(
+        &self,
+        period: Period,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<Date, Vec<&'a Day>> {
+        let mut periods: BTreeMap<Date, Vec<&Day>> = BTreeMap::new();
+        for day in days {
+            periods
+                .entry(period.start_of(day.date))
+                .or_default()
+                .push(day);
+        }
+        for days in periods.values_mut() {
+            days.sort_by_key(|day| day.date);
+            days.dedup_by_key(|day| day.date);
+        }
+        periods
+    }
+
+    /// `days` must be sorted and within `start..=end`, `five_days` are the
+    /// sums of [`five_day_sums`] over every day.
+    fn compute(
+        &self,
+        index: ClimateIndex,
+        days: &[&Day],
+        five_days: &BTreeMap<Date, f32>,
+        start: Date,
+        end: Date,
+    ) -> Option<f32> {
+        let count = |f: fn(&Day) -> bool| Some(days.iter().filter(|day| f(day)).count() as f32);
+        let wet = || days.iter().map(|day| day.rain).filter(|rain| *rain >= 1.0);
+        let longest = |f: fn(&Day) -> bool| {
+            let longest = SpellFinder::new(f)
+                .find(days.iter().copied())
+                .into_iter()
+                .map(|spell| spell.length)
+                .max();
+            Some(longest.unwrap_or(0) as f32)
+        };
+
+        match index {
+            ClimateIndex::FD => count(|day| day.low_temp < 0.0),
+            ClimateIndex::SU => count(|day| day.high_temp > 25.0),
+            ClimateIndex::ID => count(|day| day.high_temp < 0.0),
+            ClimateIndex::TR => count(|day| day.low_temp > 20.0),
+            ClimateIndex::TXx => days.iter().map(|day| day.high_temp).reduce(f32::max),
+            ClimateIndex::TNn => days.iter().map(|day| day.low_temp).reduce(f32::min),
+            ClimateIndex::TX90p => {
+                self.percent_of_days(days, &self.tx90, |day, t| day.high_temp > t)
+            }
+            ClimateIndex::TN10p => {
+                self.percent_of_days(days, &self.tn10, |day, t| day.low_temp < t)
+            }
+            ClimateIndex::DTR => (!days.is_empty()).then(|| {
+                days.iter()
+                    .map(|day| day.high_temp - day.low_temp)
+                    .sum::<f32>()
+                    / days.len() as f32
+            }),
+            ClimateIndex::GSL => {
+                if start.month() != Month::January || end.month() != Month::December {
+                    return None;
+                }
+                let july = Date::from_calendar_date(start.year(), Month::July, 1).unwrap();
+                let first = SpellFinder::new(|day: &Day| day.mean_temp > 5.0)
+                    .min_length(6)
+                    .find(days.iter().copied())
+                    .first()
+                    .map(|spell| spell.start);
+                let Some(first) = first else {
+                    return Some(0.0);
+                };
+                let last = SpellFinder::new(|day: &Day| day.mean_temp < 5.0)
+                    .min_length(6)
+                    .find(
+                        days.iter()
+                            .copied()
+                            .filter(|day| day.date >= july.max(first)),
+                    )
+                    .first()
+                    .map_or(end.next_day().unwrap(), |spell| spell.start);
+                Some((last - first).whole_days() as f32)
+            }
+            ClimateIndex::Rx1day => days.iter().map(|day| day.rain).reduce(f32::max),
+            ClimateIndex::Rx5day => five_days
+                .range(start..=end)
+                .map(|(_, rain)| *rain)
+                .reduce(f32::max),
+            ClimateIndex::R10mm => count(|day| day.rain >= 10.0),
+            ClimateIndex::R20mm => count(|day| day.rain >= 20.0),
+            ClimateIndex::CDD => longest(|day| day.rain < 1.0),
+            ClimateIndex::CWD => longest(|day| day.rain >= 1.0),
+            ClimateIndex::SDII => {
+                let count = wet().count();
+                Some(if count == 0 {
+                    0.0
+                } else {
+                    wet().sum::<f32>() / count as f32
+                })
+            }
+            ClimateIndex::PRCPTOT => Some(wet().sum()),
+        }
+    }
+
+    fn percent_of_days(
+        &self,
+        days: &[&Day],
+        thresholds: &BTreeMap<u16, f32>,
+        exceeds: fn(&Day, f32) -> bool,
+    ) -> Option<f32> {
+        let mut total = 0;
+        let mut exceeding = 0;
+        for day in days {
+            if let Some(threshold) = thresholds.get(&day_of_year(day.date)) {
+                total += 1;
+                exceeding += exceeds(day, *threshold) as usize;
+            }
+        }
+        (total != 0).then(|| exceeding as f32 * 100.0 / total as f32)
+    }
+}
+
+/// The days sorted by date, without duplicates.
+fn sorted<'a>(days: impl IntoIterator<Item = &'a Day>) -> Vec<&'a Day> {
+    let mut days: Vec<&Day> = days.into_iter().collect();
+    days.sort_by_key(|day| day.date);
+    days.dedup_by_key(|day| day.date);
+    days
+}
+
+/// The rain over 5 consecutive days, indexed by the last day. Like the ETCCDI
+/// the window of a period's first days reaches back into the previous period.
+/// `days` must be sorted without duplicates.
+fn five_day_sums(days: &[&Day]) -> BTreeMap<Date, f32> {
+    days.windows(5)
+        .filter(|window| (window[4].date - window[0].date).whole_days() == 4)
+        .map(|window| (window[4].date, window.iter().map(|day| day.rain).sum()))
+        .collect()
+}
+
+/// Number of days of `start..=end` not in `days`.
+fn missing(days: &[&Day], start: Date, end: Date) -> usize {
+    (end - start).whole_days() as usize + 1 - days.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_day;
+
+    #[test]
+    fn rx5day_reaches_back_into_the_previous_year() {
+        let mut date = Date::from_calendar_date(2022, Month::December, 1).unwrap();
+        let mut days = Vec::new();
+        while date.year() < 2024 {
+            let rain = match (date.month(), date.day()) {
+                (Month::December, 29..=31) | (Month::January, 1..=2) => 50.0,
+                _ => 0.0,
+            };
+            days.push(Day {
+                rain,
+                ..test_day(date)
+            });
+            date = date.next_day().unwrap();
+        }
+
+        let etccdi = Etccdi::new();
+        let annual = etccdi.annual(ClimateIndex::Rx5day, &days);
+        assert_eq!(annual[&2023], Some(250.0));
+        let monthly = etccdi.monthly(ClimateIndex::Rx5day, &days);
+        let january = Date::from_calendar_date(2023, Month::January, 1).unwrap();
+        assert_eq!(monthly[&january], Some(250.0));
+    }
+}
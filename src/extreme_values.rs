@@ -0,0 +1,229 @@
+use std::collections::BTreeMap;
+
+use crate::{
+    distribution::{Distribution, Gev, Gumbel},
+    percentile, Aggregation, Day, Extreme, Period, Record, Resampler, Variable,
+};
+
+/// The distribution fitted on the annual extremes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ExtremeModel {
+    /// The generalized extreme value distribution.
+    #[default]
+    Gev,
+    /// The Gumbel distribution, more stable on short series.
+    Gumbel,
+}
+
+/// The value reached or exceeded on average once every `period` years.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ReturnLevel {
+    /// In years.
+    pub period: f32,
+    pub value: f32,
+    /// Confidence interval of the value.
+    pub interval: (f32, f32),
+}
+
+/// Extreme value analysis of the annual maxima, or minima, of a variable.
+///
+/// The confidence intervals are computed by bootstrapping the annual extremes.
+#[derive(Debug, Clone)]
+pub struct ExtremeValues {
+    variable: Variable,
+    extreme: Extreme,
+    model: ExtremeModel,
+    min_coverage: f32,
+    confidence: f64,
+    resamples: usize,
+}
+
+/// The result of [`ExtremeValues::fit`].
+#[derive(Debug, Clone)]
+pub struct ExtremeValueFit {
+    pub variable: Variable,
+    pub extreme: Extreme,
+    pub model: ExtremeModel,
+    /// The extreme of each year with enough data.
+    pub annual: BTreeMap<i32, Record>,
+    /// Fitted on the annual extremes, negated for the minima.
+    pub distribution: Gev,
+    /// The confidence level of the intervals, between 0 and 1.
+    pub confidence: f64,
+    bootstrap: Vec<Gev>,
+}
+
+impl ExtremeValues {
+    /// The usual return periods in years.
+    pub const RETURN_PERIODS: [f32; 5] = [2.0, 5.0, 10.0, 50.0, 100.0];
+
+    /// A GEV fit with 95% confidence intervals, a year must be covered at 90% to be used.
+    pub fn new(variable: Variable, extreme: Extreme) -> Self {
+        Self {
+            variable,
+            extreme,
+            model: ExtremeModel::default(),
+            min_coverage: 90.0,
+            confidence: 0.95,
+            resamples: 1000,
+        }
+    }
+
+    pub fn model(mut self, model: ExtremeModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Minimum percentage of present days for a year to be used.
+    pub fn min_coverage(mut self, percentage: f32) -> Self {
+        self.min_coverage = percentage;
+        self
+    }
+
+    /// Confidence level of the intervals, between 0 and 1.
+    pub fn confidence(mut self, confidence: f64) -> Self {
+        self.confidence = confidence;
+        self
+    }
+
+    /// Number of bootstrap samples used for the confidence intervals, 1000 by default.
+    pub fn resamples(mut self, resamples: usize) -> Self {
+        self.resamples = resamples;
+        self
+    }
+
+    /// The highest or lowest value of each year with enough data.
+    pub fn annual_extremes<'a>(
+        &self,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<i32, Record> {
+        let aggregation = match self.extreme {
+            Extreme::Highest => Aggregation::Max,
+            Extreme::Lowest => Aggregation::Min,
+        };
+        Resampler::new(Period::Year)
+            .variables([self.variable])
+            .aggregation(self.variable, aggregation)
+            .min_coverage(self.min_coverage)
+            .resample(days)
+            .into_iter()
+            .filter(|year| year.complete)
+            .filter_map(|year| {
+                let aggregate = year.get(self.variable)?;
+                let record = Record {
+                    value: aggregate.value,
+                    date: aggregate.date?,
+                    time: aggregate.time,
+                };
+                Some((year.start.year(), record))
+            })
+            .collect()
+    }
+
+    /// `None` if there is less than 3 years with enough data or if the distribution can't be fitted.
+    pub fn fit<'a>(&self, days: impl IntoIterator<Item = &'a Day>) -> Option<ExtremeValueFit> {
+        let annual = self.annual_extremes(days);
+        let sign = self.sign();
+        let values: Vec<f64> = annual
+            .values()
+            .map(|record| sign * record.value as f64)
+            .collect();
+        let distribution = self.fit_values(&values)?;
+
+        let mut rng = SplitMix64(0x5eed);
+        let mut sample = vec![0.0; values.len()];
+        let bootstrap = (0..self.resamples)
+            .filter_map(|_| {
+                for value in &mut sample {
+                    *value = values[rng.next() as usize % values.len()];
+                }
+                self.fit_values(&sample)
+            })
+            .collect();
+
+        Some(ExtremeValueFit {
+            variable: self.variable,
+            extreme: self.extreme,
+            model: self.model,
+            annual,
+            distribution,
+            confidence: self.confidence,
+            bootstrap,
+        })
+    }
+
+    fn fit_values(&self, values: &[f64]) -> Option<Gev> {
+        match self.model {
+            ExtremeModel::Gev => Gev::fit(values),
+            ExtremeModel::Gumbel => Gumbel::fit(values).map(Gev::from),
+        }
+    }
+
+    fn sign(&self) -> f64 {
+        match self.extreme {
+            Extreme::Highest => 1.0,
+            Extreme::Lowest => -1.0,
+        }
+    }
+}
+
+impl ExtremeValueFit {
+    /// The value reached on average once every `period` years, which must be above 1.
+    /// For the minima it's the value reached or undercut.
+    pub fn return_level(&self, period: f32) -> ReturnLevel {
+        let sign = match self.extreme {
+            Extreme::Highest => 1.0,
+            Extreme::Lowest => -1.0,
+        };
+        let p = 1.0 - 1.0 / period as f64;
+        let value = (sign * self.distribution.quantile(p)) as f32;
+
+        let mut levels: Vec<f32> = self
+            .bootstrap
+            .iter()
+            .map(|distribution| (sign * distribution.quantile(p)) as f32)
+            .filter(|level| level.is_finite())
+            .collect();
+        levels.sort_by(f32::total_cmp);
+        let tail = (1.0 - self.confidence as f32) / 2.0 * 100.0;
+        let interval = percentile(&levels, tail)
+            .zip(percentile(&levels, 100.0 - tail))
+            .unwrap_or((value, value));
+
+        ReturnLevel {
+            period,
+            value,
+            interval,
+        }
+    }
+
+    /// The return levels of [`ExtremeValues::RETURN_PERIODS`].
+    pub fn return_levels(&self) -> Vec<ReturnLevel> {
+        ExtremeValues::RETURN_PERIODS
+            .into_iter()
+            .map(|period| self.return_level(period))
+            .collect()
+    }
+
+    /// In how many years on average `value` is reached, infinite if it's out of reach.
+    pub fn return_period(&self, value: f32) -> f32 {
+        let x = match self.extreme {
+            Extreme::Highest => value as f64,
+            Extreme::Lowest => -value as f64,
+        };
+        (1.0 / (1.0 - self.distribution.cdf(x))) as f32
+    }
+}
+
+/// A small deterministic generator, enough to pick the bootstrap samples.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e3779b97f4a7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+}
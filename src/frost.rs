@@ -0,0 +1,294 @@
+use std::collections::BTreeMap;
+
+use time::{Date, Duration, Month};
+
+use crate::{Coverage, Day, Gaps};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Hemisphere {
+    /// The frost season is split on the first of july.
+    #[default]
+    Northern,
+    /// The frost season is split on the first of january.
+    Southern,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FrostOptions {
+    /// A day is a frost day if its low is at or below the threshold.
+    pub threshold: f32,
+    pub hemisphere: Hemisphere,
+    /// Minimum percentage of present days of a half year for its frost date to
+    /// be used in the probabilities.
+    pub min_coverage: f32,
+}
+
+impl Default for FrostOptions {
+    fn default() -> Self {
+        Self {
+            threshold: 0.0,
+            hemisphere: Hemisphere::default(),
+            min_coverage: 90.0,
+        }
+    }
+}
+
+/// The frost dates of a year. In the southern hemisphere the year starts on
+/// the first of july and the autumn frost is the one of the next year.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FrostYear {
+    pub year: i32,
+    /// `None` if there was no frost during the spring.
+    pub last_spring_frost: Option<Date>,
+    /// `None` if there was no frost during the autumn.
+    pub first_autumn_frost: Option<Date>,
+    /// Days between the last spring frost and the first autumn frost.
+    pub frost_free_days: usize,
+    pub spring_coverage: Coverage,
+    pub autumn_coverage: Coverage,
+}
+
+/// The frost dates of every year and their probabilities.
+#[derive(Debug, Clone)]
+pub struct FrostDates {
+    pub options: FrostOptions,
+    pub years: Vec<FrostYear>,
+}
+
+impl Hemisphere {
+    /// The frost year containing `date`.
+    fn year_of(&self, date: Date) -> i32 {
+        match self {
+            Hemisphere::Northern => date.year(),
+            Hemisphere::Southern if (date.month() as u8) < Month::July as u8 => date.year() - 1,
+            Hemisphere::Southern => date.year(),
+        }
+    }
+
+    /// First day of the frost year and the day splitting it in two.
+    fn season(&self, year: i32) -> (Date, Date) {
+        match self {
+            Hemisphere::Northern => (
+                Date::from_calendar_date(year, Month::January, 1).unwrap(),
+                Date::from_calendar_date(year, Month::July, 1).unwrap(),
+            ),
+            Hemisphere::Southern => (
+                Date::from_calendar_date(year, Month::July, 1).unwrap(),
+                Date::from_calendar_date(year + 1, Month::January, 1).unwrap(),
+            ),
+        }
+    }
+
+    /// The start of a season containing a 29th of february.
+    fn reference_start(&self) -> Date {
+        match self {
+            Hemisphere::Northern => Date::from_calendar_date(2000, Month::January, 1).unwrap(),
+            Hemisphere::Southern => Date::from_calendar_date(1999, Month::July, 1).unwrap(),
+        }
+    }
+
+    /// The same calendar day in the season starting on [`Hemisphere::reference_start`].
+    fn reference(&self, date: Date) -> Date {
+        let year = match self {
+            Hemisphere::Southern if date.month() as u8 >= Month::July as u8 => 1999,
+            _ => 2000,
+        };
+        Date::from_calendar_date(year, date.month(), date.day()).unwrap()
+    }
+}
+
+impl FrostDates {
+    pub fn compute<'a>(days: impl IntoIterator<Item = &'a Day>, options: FrostOptions) -> Self {
+        let mut seasons: BTreeMap<i32, Vec<&Day>> = BTreeMap::new();
+        for day in days {
+            seasons
+                .entry(options.hemisphere.year_of(day.date))
+                .or_default()
+                .push(day);
+        }
+        let (Some(first), Some(last)) = (
+            seasons.keys().next().copied(),
+            seasons.keys().next_back().copied(),
+        ) else {
+            return Self {
+                options,
+                years: Vec::new(),
+            };
+        };
+
+        let years = (first..=last)
+            .map(|year| {
+                let days = seasons.remove(&year).unwrap_or_default();
+                let (start, split) = options.hemisphere.season(year);
+                let (next_start, _) = options.hemisphere.season(year + 1);
+                let end = next_start.previous_day().unwrap();
+
+                let frosts = days
+                    .iter()
+                    .filter(|day| day.low_temp <= options.threshold)
+                    .map(|day| day.date);
+                let last_spring_frost = frosts.clone().filter(|date| *date < split).max();
+                let first_autumn_frost = frosts.filter(|date| *date >= split).min();
+
+                let free_start = last_spring_frost.map_or(start, |date| date.next_day().unwrap());
+                let free_end = first_autumn_frost.map_or(end, |date| date.previous_day().unwrap());
+
+                FrostYear {
+                    year,
+                    last_spring_frost,
+                    first_autumn_frost,
+                    frost_free_days: ((free_end - free_start).whole_days() + 1).max(0) as usize,
+                    spring_coverage: Gaps::new(
+                        days.iter().copied(),
+                        start..=split.previous_day().unwrap(),
+                    )
+                    .total,
+                    autumn_coverage: Gaps::new(days.iter().copied(), split..=end).total,
+                }
+            })
+            .collect();
+
+        Self { options, years }
+    }
+
+    /// The calendar day the last spring frost happened on or before in
+    /// `probability` (between 0 and 1) of the years. With `0.9` it means there
+    /// is a 90% chance of no frost after this day.
+    /// `None` if there is no year or if there was no spring frost in that many years.
+    pub fn last_spring_frost(&self, probability: f32) -> Option<(Month, u8)> {
+        let mut offsets: Vec<i64> = self
+            .years
+            .iter()
+            .filter(|year| year.spring_coverage.percentage() >= self.options.min_coverage)
+            .map(|year| match year.last_spring_frost {
+                Some(date) => self.offset(date),
+                None => i64::MIN,
+            })
+            .collect();
+        offsets.sort_unstable();
+        let offset = *offsets.get(self.rank(probability, offsets.len()) - 1)?;
+        self.calendar_day(offset)
+    }
+
+    /// The calendar day the first autumn frost happened on or after in
+    /// `probability` (between 0 and 1) of the years. With `0.9` it means there
+    /// is a 90% chance of no frost before this day.
+    /// `None` if there is no year or if there was no autumn frost in that many years.
+    pub fn first_autumn_frost(&self, probability: f32) -> Option<(Month, u8)> {
+        let mut offsets: Vec<i64> = self
+            .years
+            .iter()
+            .filter(|year| year.autumn_coverage.percentage() >= self.options.min_coverage)
+            .map(|year| match year.first_autumn_frost {
+                Some(date) => self.offset(date),
+                None => i64::MAX,
+            })
+            .collect();
+        offsets.sort_unstable();
+        let offset = *offsets.get(
+            offsets
+                .len()
+                .checked_sub(self.rank(probability, offsets.len()))?,
+        )?;
+        self.calendar_day(offset)
+    }
+
+    /// The frost-free season length reached or exceeded in `probability`
+    /// (between 0 and 1) of the years. Only the years with enough data in both
+    /// halves are considered.
+    pub fn frost_free_days(&self, probability: f32) -> Option<usize> {
+        let mut lengths: Vec<usize> = self
+            .years
+            .iter()
+            .filter(|year| {
+                year.spring_coverage.percentage() >= self.options.min_coverage
+                    && year.autumn_coverage.percentage() >= self.options.min_coverage
+            })
+            .map(|year| year.frost_free_days)
+            .collect();
+        lengths.sort_unstable();
+        lengths
+            .get(
+                lengths
+                    .len()
+                    .checked_sub(self.rank(probability, lengths.len()))?,
+            )
+            .copied()
+    }
+
+    /// Number of values needed to reach the probability, at least one.
+    fn rank(&self, probability: f32, len: usize) -> usize {
+        ((probability * len as f32).ceil() as usize).clamp(1, len.max(1))
+    }
+
+    /// Days between the start of the season and the date, counted on the
+    /// reference season so a calendar day always has the same offset.
+    fn offset(&self, date: Date) -> i64 {
+        let hemisphere = self.options.hemisphere;
+        (hemisphere.reference(date) - hemisphere.reference_start()).whole_days()
+    }
+
+    fn calendar_day(&self, offset: i64) -> Option<(Month, u8)> {
+        if offset == i64::MIN || offset == i64::MAX {
+            return None;
+        }
+        let date = self.options.hemisphere.reference_start() + Duration::days(offset);
+        Some((date.month(), date.day()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_day;
+
+    /// Every day from `first` to `last` with a frost on the `frosts` dates.
+    fn days(first: Date, last: Date, frosts: &[Date]) -> Vec<Day> {
+        let mut days = Vec::new();
+        let mut date = first;
+        while date <= last {
+            let low_temp = if frosts.contains(&date) { -2.0 } else { 10.0 };
+            days.push(Day {
+                low_temp,
+                ..test_day(date)
+            });
+            date = date.next_day().unwrap();
+        }
+        days
+    }
+
+    fn date(year: i32, month: Month, day: u8) -> Date {
+        Date::from_calendar_date(year, month, day).unwrap()
+    }
+
+    #[test]
+    fn frost_dates_after_february_of_a_common_year() {
+        let days = days(
+            date(2023, Month::January, 1),
+            date(2023, Month::December, 31),
+            &[date(2023, Month::April, 15), date(2023, Month::October, 16)],
+        );
+        let frost = FrostDates::compute(&days, FrostOptions::default());
+        assert_eq!(frost.last_spring_frost(1.0), Some((Month::April, 15)));
+        assert_eq!(frost.first_autumn_frost(1.0), Some((Month::October, 16)));
+    }
+
+    #[test]
+    fn southern_frost_dates_after_february_of_a_common_year() {
+        let days = days(
+            date(2022, Month::July, 1),
+            date(2023, Month::June, 30),
+            &[
+                date(2022, Month::September, 10),
+                date(2023, Month::April, 15),
+            ],
+        );
+        let options = FrostOptions {
+            hemisphere: Hemisphere::Southern,
+            ..FrostOptions::default()
+        };
+        let frost = FrostDates::compute(&days, options);
+        assert_eq!(frost.last_spring_frost(1.0), Some((Month::September, 10)));
+        assert_eq!(frost.first_autumn_frost(1.0), Some((Month::April, 15)));
+    }
+}
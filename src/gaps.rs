@@ -0,0 +1,120 @@
+use std::{collections::BTreeMap, ops::RangeInclusive};
+
+use time::Date;
+
+use crate::{last_day_of_month, Archive, Day, Report};
+
+/// How many days are present out of the expected ones.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Coverage {
+    pub expected: usize,
+    pub present: usize,
+}
+
+impl Coverage {
+    pub fn missing(&self) -> usize {
+        self.expected - self.present
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.present == self.expected
+    }
+
+    /// Percentage of present days, between `0.0` and `100.0`.
+    /// An empty period is considered fully covered.
+    pub fn percentage(&self) -> f32 {
+        if self.expected == 0 {
+            100.0
+        } else {
+            self.present as f32 * 100.0 / self.expected as f32
+        }
+    }
+}
+
+/// The holes in the data over a range of dates.
+#[derive(Debug, Clone, Default)]
+pub struct Gaps {
+    /// Every day of the range without data, including the days of the missing months.
+    pub missing_days: Vec<Date>,
+    /// First day of the months of the range without a single day of data.
+    pub missing_months: Vec<Date>,
+    /// Coverage of each month of the range, indexed by the first day of the month.
+    pub months: BTreeMap<Date, Coverage>,
+    /// Coverage of each year of the range.
+    pub years: BTreeMap<i32, Coverage>,
+    /// Coverage of the whole range.
+    pub total: Coverage,
+}
+
+impl Gaps {
+    /// Only the days within `range` are considered.
+    /// Months and years partially covered by the range only expect the days in the range.
+    pub fn new<'a>(days: impl IntoIterator<Item = &'a Day>, range: RangeInclusive<Date>) -> Self {
+        let mut present: Vec<Date> = days
+            .into_iter()
+            .map(|day| day.date)
+            .filter(|date| range.contains(date))
+            .collect();
+        present.sort_unstable();
+        present.dedup();
+
+        let mut gaps = Self::default();
+        let mut present = present.into_iter().peekable();
+        let mut date = *range.start();
+        while date <= *range.end() {
+            let is_present = present.next_if_eq(&date).is_some();
+
+            let month = date.replace_day(1).unwrap();
+            for coverage in [
+                gaps.months.entry(month).or_default(),
+                gaps.years.entry(date.year()).or_default(),
+                &mut gaps.total,
+            ] {
+                coverage.expected += 1;
+                coverage.present += is_present as usize;
+            }
+            if !is_present {
+                gaps.missing_days.push(date);
+            }
+
+            date = match date.next_day() {
+                Some(date) => date,
+                None => break,
+            };
+        }
+
+        gaps.missing_months = gaps
+            .months
+            .iter()
+            .filter(|(_, coverage)| coverage.present == 0)
+            .map(|(month, _)| *month)
+            .collect();
+
+        gaps
+    }
+}
+
+impl Report {
+    /// Gaps over the month of the report.
+    pub fn gaps(&self) -> Gaps {
+        let first = self.metadata.date;
+        Gaps::new(&self.days, first..=last_day_of_month(first))
+    }
+}
+
+impl Archive {
+    /// Gaps over an arbitrary range of dates.
+    pub fn gaps(&self, range: RangeInclusive<Date>) -> Gaps {
+        Gaps::new(self.days(), range)
+    }
+
+    /// Gaps from the first to the last month of the archive.
+    pub fn all_gaps(&self) -> Gaps {
+        let (Some(first), Some(last)) = (self.reports().next(), self.reports().next_back()) else {
+            return Gaps::default();
+        };
+        let first = first.metadata.date.replace_day(1).unwrap();
+        let last = last_day_of_month(last.metadata.date);
+        self.gaps(first..=last)
+    }
+}
@@ -1,9 +1,59 @@
-use std::{cmp::Ordering, ops::Range, str::FromStr};
+use std::{cmp::Ordering, collections::BTreeMap, str::FromStr};
 
 use logos::Logos;
 use thiserror::Error;
 use time::{Date, Month, PrimitiveDateTime};
 
+mod anomaly;
+mod archive;
+mod compare;
+mod degree_days;
+mod distribution;
+mod empirical;
+mod et0;
+mod etccdi;
+mod extreme_values;
+mod frost;
+mod gaps;
+mod normals;
+mod on_this_day;
+mod query;
+mod records;
+mod resample;
+mod rolling;
+mod spells;
+mod spi;
+mod stats;
+mod summary;
+mod time_of_day;
+mod trend;
+mod variable;
+
+pub use anomaly::Anomaly;
+pub use archive::{Archive, ArchiveError};
+pub use compare::{ComparisonRow, PeriodComparison, Statistic};
+pub use degree_days::{DegreeDayMethod, DegreeDays, LoggerComparison};
+pub use distribution::{Distribution, Gamma, Gev, Gumbel, ZeroInflated};
+pub use empirical::{Bins, Empirical, FiveNumber, Histogram, Kde};
+pub use et0::{extraterrestrial_radiation, WaterBalance};
+pub use etccdi::{ClimateIndex, Etccdi};
+pub use extreme_values::{ExtremeModel, ExtremeValueFit, ExtremeValues, ReturnLevel};
+pub use frost::{FrostDates, FrostOptions, FrostYear, Hemisphere};
+pub use gaps::{Coverage, Gaps};
+pub use normals::{Normal, Normals, NormalsOptions};
+pub use on_this_day::{OnThisDay, OnThisDayComparison};
+pub use query::{Comparison, DaysQuery, Filter, Query};
+pub use records::{BrokenRecord, Extreme, Ranking, Record, RecordScope, Records};
+pub use resample::{aggregate, Aggregate, Period, Resampled, Resampler, Season};
+pub use rolling::{ewma, Alignment, RollingWindow};
+pub use spells::{GapPolicy, Spell, SpellFinder};
+pub use spi::{DroughtCategory, Spi, SpiValue};
+pub use stats::{percentile, Stats};
+pub use summary::Summary;
+pub use time_of_day::{TimeOfDay, UnusualTime};
+pub use trend::{MannKendall, Trend};
+pub use variable::{Aggregation, Unit, Variable};
+
 #[derive(Logos, Debug, PartialEq)]
 #[logos(skip r"[ \t]+")] // Ignore this regex pattern between tokens
 enum Token {
@@ -64,80 +114,147 @@ enum Token {
     Dot,
 }
 
-#[derive(Debug, Clone)]
+/// Two reports are equal if they have the same metadata and exactly the same days.
+/// Use [`Report::key`] to identify a report and [`Report::approx_eq`] to
+/// compare reports that may differ by a rounding error.
+#[derive(Debug, Clone, PartialEq)]
 pub struct Report {
     pub metadata: Metadata,
     // Days should be sorted by date
     pub days: Vec<Day>,
 }
 
-impl PartialEq for Report {
-    fn eq(&self, other: &Self) -> bool {
-        self.metadata.date == other.metadata.date
-    }
+/// Identify a report: the station it comes from and its month.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ReportKey {
+    pub station: StationId,
+    // First day of the month
+    pub month: Date,
 }
 
-impl Eq for Report {}
-
-impl PartialOrd for Report {
-    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
-        self.metadata.date.partial_cmp(&other.metadata.date)
+impl Report {
+    pub fn key(&self) -> ReportKey {
+        ReportKey {
+            station: self.metadata.station_id(),
+            month: self.metadata.date,
+        }
     }
-}
 
-impl Ord for Report {
-    fn cmp(&self, other: &Self) -> Ordering {
-        self.metadata.date.cmp(&other.metadata.date)
+    /// Same metadata and days, with the measurements allowed to differ by `tolerance`.
+    pub fn approx_eq(&self, other: &Self, tolerance: f32) -> bool {
+        self.metadata == other.metadata
+            && self.days.len() == other.days.len()
+            && self
+                .days
+                .iter()
+                .zip(&other.days)
+                .all(|(left, right)| left.approx_eq(right, tolerance))
     }
-}
 
-impl Report {
-    pub fn first_date(&self) -> Date {
-        self.days.first().unwrap().date
+    /// `None` if the report doesn't contain any day.
+    pub fn first_date(&self) -> Option<Date> {
+        self.days.first().map(|day| day.date)
     }
 
-    pub fn last_date(&self) -> Date {
-        self.days.last().unwrap().date
+    /// `None` if the report doesn't contain any day.
+    pub fn last_date(&self) -> Option<Date> {
+        self.days.last().map(|day| day.date)
     }
 
-    pub fn range<T>(&self, retrieve: fn(&Day) -> T, compare: fn(&T, &T) -> Ordering) -> Range<T> {
-        self.days.iter().map(retrieve).min_by(compare).unwrap()
-            ..self.days.iter().map(retrieve).max_by(compare).unwrap()
+    /// The inclusive `(min, max)` of a measurement, `None` if the report doesn't contain any day.
+    pub fn range<T>(
+        &self,
+        retrieve: fn(&Day) -> T,
+        compare: fn(&T, &T) -> Ordering,
+    ) -> Option<(T, T)> {
+        let min = self.days.iter().map(retrieve).min_by(compare)?;
+        let max = self.days.iter().map(retrieve).max_by(compare)?;
+        Some((min, max))
     }
 
-    pub fn temperature_range(&self) -> Range<f32> {
-        self.days
+    /// The inclusive `(lowest low, highest high)`, `None` if the report doesn't contain any day.
+    pub fn temperature_range(&self) -> Option<(f32, f32)> {
+        let low = self
+            .days
             .iter()
             .map(|day| day.low_temp)
-            .min_by(|left, right| left.total_cmp(right))
-            .unwrap()
-            ..self
-                .days
-                .iter()
-                .map(|day| day.high_temp)
-                .max_by(|left, right| left.total_cmp(right))
-                .unwrap()
+            .min_by(|left, right| left.total_cmp(right))?;
+        let high = self
+            .days
+            .iter()
+            .map(|day| day.high_temp)
+            .max_by(|left, right| left.total_cmp(right))?;
+        Some((low, high))
+    }
+
+    /// Can only merge reports from the exact same header, except for the date.
+    /// The reports can be in any order and overlap, when both reports
+    /// contains a different version of the same day it'll return an error.
+    /// See [`Report::merge_with`] to choose another [`ConflictPolicy`].
+    pub fn merge(&mut self, other: Self) -> Result<(), MergeError> {
+        self.merge_with(other, ConflictPolicy::default())
     }
 
-    /// Can only merge reports from the exact same header
-    /// Except the date which **must** change or it'll return an error.
-    pub fn merge(&mut self, mut other: Self) -> Result<(), String> {
-        if self.metadata != other.metadata {
-            return Err(String::from("Metada differs"));
+    /// Merge the days of `other` in `self`, the days stay sorted and unique.
+    /// On error `self` is left untouched.
+    pub fn merge_with(&mut self, other: Self, policy: ConflictPolicy) -> Result<(), MergeError> {
+        if self.metadata.station_id() != other.metadata.station_id() {
+            return Err(MergeError::MetadataDiffers);
         }
 
-        if self.metadata.date < other.metadata.date {
-            self.days.append(&mut other.days);
-        } else {
-            self.metadata.date = other.metadata.date;
-            other.days.append(&mut self.days);
-            self.days = other.days;
+        let mut days: BTreeMap<Date, Day> = BTreeMap::new();
+        for day in self.days.iter().chain(&other.days) {
+            let existing = match days.get_mut(&day.date) {
+                Some(existing) if existing != day => existing,
+                Some(_) => continue,
+                None => {
+                    days.insert(day.date, day.clone());
+                    continue;
+                }
+            };
+
+            match policy {
+                ConflictPolicy::KeepFirst => (),
+                ConflictPolicy::KeepLast => *existing = day.clone(),
+                ConflictPolicy::Error => return Err(MergeError::Conflict(day.date)),
+                ConflictPolicy::PreferNonMissing => {
+                    existing.high_wind_speed_date =
+                        existing.high_wind_speed_date.or(day.high_wind_speed_date);
+                    existing.wind_direction = existing.wind_direction.or(day.wind_direction);
+                }
+            }
         }
 
+        self.metadata.date = self.metadata.date.min(other.metadata.date);
+        self.days = days.into_values().collect();
+
         Ok(())
     }
 }
 
+/// What to do when two merged reports contains a different version of the same day.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// Keep the day of the report we're merging into.
+    KeepFirst,
+    /// Keep the day of the report being merged.
+    KeepLast,
+    /// Stop the merge with a [`MergeError::Conflict`].
+    #[default]
+    Error,
+    /// Keep the day of the report we're merging into but fill its missing
+    /// fields with the ones of the report being merged.
+    PreferNonMissing,
+}
+
+#[derive(Debug, Error)]
+pub enum MergeError {
+    #[error("Metadata differs")]
+    MetadataDiffers,
+    #[error("Conflicting values for the day {0}")]
+    Conflict(Date),
+}
+
 #[derive(Debug, Error)]
 pub enum ParseError {
     #[error(transparent)]
@@ -188,7 +305,7 @@ impl FromStr for Report {
     }
 }
 
-#[derive(Debug, Clone, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Metadata {
     // Date of the beginning of the month, doesn't take into account the fact
     // that days may be missing. Do not rely on it
@@ -203,15 +320,16 @@ pub struct Metadata {
     pub long: (u8, u8, u8),
 }
 
-impl PartialEq for Metadata {
-    fn eq(&self, other: &Self) -> bool {
-        self.name.eq(&other.name)
-            && self.city.eq(&other.city)
-            && self.state.eq(&other.state)
-            && self.elevation.eq(&other.elevation)
-            && self.lat.eq(&other.lat)
-            && self.long.eq(&other.long)
-    }
+/// Everything that identifies a station in the header of a report.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StationId {
+    pub name: String,
+    pub city: String,
+    pub state: String,
+
+    pub elevation: usize,
+    pub lat: (u8, u8, u8),
+    pub long: (u8, u8, u8),
 }
 
 #[derive(Debug, Error)]
@@ -227,6 +345,31 @@ pub enum MetadataError {
 }
 
 impl Metadata {
+    /// In decimal degrees. The header doesn't give the hemisphere yet, it's
+    /// considered to be in the north.
+    pub fn latitude(&self) -> f64 {
+        let (degrees, minutes, seconds) = self.lat;
+        degrees as f64 + minutes as f64 / 60.0 + seconds as f64 / 3600.0
+    }
+
+    /// In decimal degrees. The header doesn't give the hemisphere yet, it's
+    /// considered to be in the east.
+    pub fn longitude(&self) -> f64 {
+        let (degrees, minutes, seconds) = self.long;
+        degrees as f64 + minutes as f64 / 60.0 + seconds as f64 / 3600.0
+    }
+
+    pub fn station_id(&self) -> StationId {
+        StationId {
+            name: self.name.clone(),
+            city: self.city.clone(),
+            state: self.state.clone(),
+            elevation: self.elevation,
+            lat: self.lat,
+            long: self.long,
+        }
+    }
+
     pub fn parse<'a>(mut lines: impl Iterator<Item = &'a str>) -> Result<Self, MetadataError> {
         let title = lines.next().ok_or(MetadataError::MissingTitle)?;
         let mut title = Token::lexer(title);
@@ -287,7 +430,7 @@ impl Metadata {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Day {
     pub date: Date,
 
@@ -297,6 +440,10 @@ pub struct Day {
     pub low_temp: f32,
     pub low_temp_date: PrimitiveDateTime,
 
+    // Computed by the station with its own base and method, see the footer of the report
+    pub heat_deg_days: f32,
+    pub cool_deg_days: f32,
+
     pub rain: f32,
 
     pub avg_wind_speed: f32,
@@ -318,6 +465,25 @@ pub enum ParseDayError {
 }
 
 impl Day {
+    /// Same date, times and directions, with the measurements allowed to differ by `tolerance`.
+    pub fn approx_eq(&self, other: &Self, tolerance: f32) -> bool {
+        let close = |left: f32, right: f32| (left - right).abs() <= tolerance;
+
+        self.date == other.date
+            && self.high_temp_date == other.high_temp_date
+            && self.low_temp_date == other.low_temp_date
+            && self.high_wind_speed_date == other.high_wind_speed_date
+            && self.wind_direction == other.wind_direction
+            && close(self.mean_temp, other.mean_temp)
+            && close(self.high_temp, other.high_temp)
+            && close(self.low_temp, other.low_temp)
+            && close(self.heat_deg_days, other.heat_deg_days)
+            && close(self.cool_deg_days, other.cool_deg_days)
+            && close(self.rain, other.rain)
+            && close(self.avg_wind_speed, other.avg_wind_speed)
+            && close(self.high_wind_speed, other.high_wind_speed)
+    }
+
     pub fn parse(date: Date, s: &str) -> Result<Self, ParseDayError> {
         let mut day = Token::lexer(s);
         let day_number = match day.next() {
@@ -386,14 +552,13 @@ impl Day {
             .with_hms(hour, minute, 0)
             .map_err(|e| ParseDayError::BadThing(e.to_string()))?;
 
-        // skip the heat deg days and cool deg days
-        match day.next() {
-            Some(Ok(Token::Number)) => day.slice(),
-            _ => return Err(ParseDayError::BadThing(String::from("heat truc"))),
+        let heat_deg_days = match day.next() {
+            Some(Ok(Token::Number)) => day.slice().parse().unwrap(),
+            _ => return Err(ParseDayError::BadThing(String::from("Bad heat deg days"))),
         };
-        match day.next() {
-            Some(Ok(Token::Number)) => day.slice(),
-            _ => return Err(ParseDayError::BadThing(String::from("heat truc"))),
+        let cool_deg_days = match day.next() {
+            Some(Ok(Token::Number)) => day.slice().parse().unwrap(),
+            _ => return Err(ParseDayError::BadThing(String::from("Bad cool deg days"))),
         };
 
         let rain = match day.next() {
@@ -457,6 +622,8 @@ impl Day {
             high_temp_date,
             low_temp,
             low_temp_date,
+            heat_deg_days,
+            cool_deg_days,
             rain,
             avg_wind_speed,
             high_wind_speed,
@@ -466,7 +633,7 @@ impl Day {
     }
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum Direction {
     N,
     NNE,
@@ -486,6 +653,39 @@ pub enum Direction {
     NNW,
 }
 
+impl Direction {
+    /// Clockwise from the north.
+    pub const ALL: [Direction; 16] = [
+        Self::N,
+        Self::NNE,
+        Self::NE,
+        Self::ENE,
+        Self::E,
+        Self::ESE,
+        Self::SE,
+        Self::SSE,
+        Self::S,
+        Self::SSW,
+        Self::SW,
+        Self::WSW,
+        Self::W,
+        Self::WNW,
+        Self::NW,
+        Self::NNW,
+    ];
+
+    /// Clockwise angle from the north, between `0.0` and `337.5`.
+    pub fn degrees(self) -> f32 {
+        self as usize as f32 * 22.5
+    }
+
+    /// The closest direction to a clockwise angle from the north.
+    pub fn from_degrees(degrees: f32) -> Self {
+        let idx = (degrees.rem_euclid(360.0) / 22.5).round() as usize % Self::ALL.len();
+        Self::ALL[idx]
+    }
+}
+
 impl FromStr for Direction {
     type Err = String;
 
@@ -559,3 +759,48 @@ impl FromStr for WindSpeedUnit {
         }
     }
 }
+
+pub(crate) fn last_day_of_month(date: Date) -> Date {
+    let (year, month) = match date.month() {
+        Month::December => (date.year() + 1, Month::January),
+        month => (date.year(), month.next()),
+    };
+    Date::from_calendar_date(year, month, 1)
+        .unwrap()
+        .previous_day()
+        .unwrap()
+}
+
+/// The day of the year as if every year was a leap year, so a calendar day
+/// always has the same index, between `1` and `366`.
+pub(crate) fn day_of_year(date: Date) -> u16 {
+    Date::from_calendar_date(2000, date.month(), date.day())
+        .unwrap()
+        .ordinal()
+}
+
+/// The same calendar day in `year`, the 29 february becoming the 28 on common years.
+pub(crate) fn same_day_in_year(date: Date, year: i32) -> Date {
+    date.replace_year(year)
+        .unwrap_or_else(|_| Date::from_calendar_date(year, Month::February, 28).unwrap())
+}
+
+/// A day of the given date with every value at zero, for the tests.
+#[cfg(test)]
+pub(crate) fn test_day(date: Date) -> Day {
+    Day {
+        date,
+        mean_temp: 0.0,
+        high_temp: 0.0,
+        high_temp_date: date.midnight(),
+        low_temp: 0.0,
+        low_temp_date: date.midnight(),
+        heat_deg_days: 0.0,
+        cool_deg_days: 0.0,
+        rain: 0.0,
+        avg_wind_speed: 0.0,
+        high_wind_speed: 0.0,
+        high_wind_speed_date: None,
+        wind_direction: None,
+    }
+}
@@ -0,0 +1,172 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    ops::RangeInclusive,
+};
+
+use time::{Date, Month};
+
+use crate::{day_of_year, Aggregation, Archive, Day, Period, Resampler, Stats, Variable};
+
+/// Climatological normals of an archive over a reference period.
+///
+/// The monthly normals are computed on the monthly totals of the summed
+/// variables and on the monthly means of the others. The daily normals pool
+/// the daily values of every year within a window around each day of the year.
+/// The wind direction doesn't have normals.
+#[derive(Debug, Clone)]
+pub struct Normals {
+    pub years: RangeInclusive<i32>,
+    pub options: NormalsOptions,
+    monthly: BTreeMap<(Variable, u8), Normal>,
+    // Indexed by the day of the year of a leap year
+    daily: BTreeMap<(Variable, u16), Normal>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct NormalsOptions {
+    /// Minimum number of years with data for a normal to be valid.
+    pub min_years: usize,
+    /// Minimum percentage of present days for a month to be used in the monthly normals.
+    pub min_month_coverage: f32,
+    /// Number of days taken before and after each day of the year for the daily normals.
+    pub smoothing: u16,
+}
+
+impl Default for NormalsOptions {
+    fn default() -> Self {
+        Self {
+            min_years: 10,
+            min_month_coverage: 90.0,
+            smoothing: 7,
+        }
+    }
+}
+
+/// The reference distribution of a variable for a month or a day of the year.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Normal {
+    pub stats: Stats,
+    /// Number of distinct years the values come from.
+    pub years: usize,
+    /// `true` if there is enough years of data, see [`NormalsOptions::min_years`].
+    pub valid: bool,
+    /// The sorted values the normal was computed on.
+    pub values: Vec<f32>,
+}
+
+impl Normal {
+    pub(crate) fn new(mut values: Vec<f32>, years: usize, min_years: usize) -> Option<Self> {
+        values.sort_by(f32::total_cmp);
+        Some(Self {
+            stats: Stats::new(&values)?,
+            years,
+            valid: years >= min_years,
+            values,
+        })
+    }
+}
+
+impl Normals {
+    pub fn compute(archive: &Archive, years: RangeInclusive<i32>) -> Self {
+        Self::compute_with(archive, years, NormalsOptions::default())
+    }
+
+    pub fn compute_with(
+        archive: &Archive,
+        years: RangeInclusive<i32>,
+        options: NormalsOptions,
+    ) -> Self {
+        let days: Vec<&Day> = archive
+            .days()
+            .filter(|day| years.contains(&day.date.year()))
+            .collect();
+        let variables: Vec<Variable> = Variable::ALL
+            .into_iter()
+            .filter(|variable| *variable != Variable::WindDirection)
+            .collect();
+
+        let mut resampler = Resampler::new(Period::Month)
+            .min_coverage(options.min_month_coverage)
+            .variables(variables.iter().copied());
+        for variable in &variables {
+            resampler = resampler.aggregation(*variable, normal_aggregation(*variable));
+        }
+
+        let mut monthly: BTreeMap<(Variable, u8), Vec<f32>> = BTreeMap::new();
+        for month in resampler.resample(days.iter().copied()) {
+            if !month.complete {
+                continue;
+            }
+            for (variable, aggregate) in month.values {
+                monthly
+                    .entry((variable, month.start.month() as u8))
+                    .or_default()
+                    .push(aggregate.value);
+            }
+        }
+        // Every month comes from a different year
+        let monthly = monthly
+            .into_iter()
+            .filter_map(|(key, values)| {
+                let years = values.len();
+                Some((key, Normal::new(values, years, options.min_years)?))
+            })
+            .collect();
+
+        let mut by_day: BTreeMap<(Variable, u16), Vec<(i32, f32)>> = BTreeMap::new();
+        for day in &days {
+            for variable in &variables {
+                if let Some(value) = day.get(*variable) {
+                    by_day
+                        .entry((*variable, day_of_year(day.date)))
+                        .or_default()
+                        .push((day.date.year(), value));
+                }
+            }
+        }
+
+        let mut daily = BTreeMap::new();
+        for variable in &variables {
+            for doy in 1..=366 {
+                let mut values = Vec::new();
+                let mut years = BTreeSet::new();
+                for offset in -(options.smoothing as i32)..=options.smoothing as i32 {
+                    let doy = (doy as i32 - 1 + offset).rem_euclid(366) as u16 + 1;
+                    for (year, value) in by_day.get(&(*variable, doy)).into_iter().flatten() {
+                        values.push(*value);
+                        years.insert(*year);
+                    }
+                }
+                if let Some(normal) = Normal::new(values, years.len(), options.min_years) {
+                    daily.insert((*variable, doy), normal);
+                }
+            }
+        }
+
+        Self {
+            years,
+            options,
+            monthly,
+            daily,
+        }
+    }
+
+    /// The normal of the monthly values of the variable, `None` if there is no data.
+    pub fn monthly(&self, variable: Variable, month: Month) -> Option<&Normal> {
+        self.monthly.get(&(variable, month as u8))
+    }
+
+    /// The normal of the daily values of the variable around this day of the year,
+    /// `None` if there is no data.
+    pub fn daily(&self, variable: Variable, date: Date) -> Option<&Normal> {
+        self.daily.get(&(variable, day_of_year(date)))
+    }
+}
+
+/// How the daily values are combined over a month or a period to be compared with the normals.
+pub(crate) fn normal_aggregation(variable: Variable) -> Aggregation {
+    match variable.aggregation() {
+        Aggregation::Sum => Aggregation::Sum,
+        _ => Aggregation::Mean,
+    }
+}
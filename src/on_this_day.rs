@@ -0,0 +1,134 @@
+use std::collections::BTreeMap;
+
+use time::{Date, Duration, Month};
+
+use crate::{aggregate, same_day_in_year, Archive, Day, Variable};
+
+/// A calendar day across the years, like every 14 november of the archive.
+#[derive(Debug, Clone, PartialEq)]
+pub struct OnThisDay<'a> {
+    pub month: Month,
+    pub day: u8,
+    /// Number of days taken before and after the calendar day.
+    pub window: u16,
+    /// The days of the window of every year, sorted.
+    /// The year is the one of the calendar day, not of the days around it.
+    pub years: BTreeMap<i32, Vec<&'a Day>>,
+}
+
+/// How a year compares to the others on a calendar day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OnThisDayComparison {
+    pub variable: Variable,
+    pub year: i32,
+    /// `None` if the year has no value.
+    pub value: Option<f32>,
+    /// 1 for the highest value, the ties share the best rank.
+    pub rank: Option<usize>,
+    /// Number of years with a value.
+    pub count: usize,
+    pub mean: f32,
+    /// The year of the lowest value and its value.
+    pub min: (i32, f32),
+    /// The year of the highest value and its value.
+    pub max: (i32, f32),
+}
+
+impl<'a> OnThisDay<'a> {
+    /// Keep the days within `window` days of the calendar day, every year.
+    /// On common years the 29 february is replaced by the 28.
+    pub fn new(
+        days: impl IntoIterator<Item = &'a Day>,
+        month: Month,
+        day: u8,
+        window: u16,
+    ) -> Self {
+        let mut years: BTreeMap<i32, Vec<&Day>> = BTreeMap::new();
+        let window_duration = Duration::days(window as i64);
+        for d in days {
+            // The window can overlap the previous or next year
+            for year in d.date.year() - 1..=d.date.year() + 1 {
+                let Some(center) = calendar_day(year, month, day) else {
+                    continue;
+                };
+                if (d.date - center).abs() <= window_duration {
+                    years.entry(year).or_default().push(d);
+                }
+            }
+        }
+        for days in years.values_mut() {
+            days.sort_by_key(|day| day.date);
+            days.dedup_by_key(|day| day.date);
+        }
+
+        Self {
+            month,
+            day,
+            window,
+            years,
+        }
+    }
+
+    /// The calendar day itself in `year`.
+    pub fn day(&self, year: i32) -> Option<&'a Day> {
+        let date = calendar_day(year, self.month, self.day)?;
+        self.years
+            .get(&year)?
+            .iter()
+            .find(|day| day.date == date)
+            .copied()
+    }
+
+    /// The value of every year, aggregated over the window with the default
+    /// aggregation of the variable. The years missing a day of their window are skipped.
+    pub fn values(&self, variable: Variable) -> BTreeMap<i32, f32> {
+        let expected = 2 * self.window as usize + 1;
+        self.years
+            .iter()
+            .filter(|(_, days)| days.len() == expected)
+            .filter_map(|(year, days)| {
+                let aggregate = aggregate(days, variable, variable.aggregation())?;
+                // A missing value makes the window incomplete too
+                (aggregate.count == expected).then_some((*year, aggregate.value))
+            })
+            .collect()
+    }
+
+    /// Compare `year` to every year. `None` if no year has a value.
+    pub fn compare(&self, variable: Variable, year: i32) -> Option<OnThisDayComparison> {
+        let values = self.values(variable);
+        let count = values.len();
+        let (min, max) = values.iter().fold(None, |acc, (year, value)| {
+            let (min, max) = acc.unwrap_or(((*year, *value), (*year, *value)));
+            Some((
+                if *value < min.1 { (*year, *value) } else { min },
+                if *value > max.1 { (*year, *value) } else { max },
+            ))
+        })?;
+        let value = values.get(&year).copied();
+
+        Some(OnThisDayComparison {
+            variable,
+            year,
+            value,
+            rank: value.map(|value| values.values().filter(|other| **other > value).count() + 1),
+            count,
+            mean: values.values().sum::<f32>() / count as f32,
+            min,
+            max,
+        })
+    }
+}
+
+impl Archive {
+    /// See [`OnThisDay::new`].
+    pub fn on_this_day(&self, month: Month, day: u8, window: u16) -> OnThisDay<'_> {
+        OnThisDay::new(self.days(), month, day, window)
+    }
+}
+
+/// `None` if the calendar day doesn't exist, even on leap years.
+fn calendar_day(year: i32, month: Month, day: u8) -> Option<Date> {
+    let date = Date::from_calendar_date(2000, month, day).ok()?;
+    Some(same_day_in_year(date, year))
+}
@@ -0,0 +1,162 @@
+use std::ops::{self, RangeInclusive};
+
+use time::{Date, Month};
+
+use crate::{aggregate, day_of_year, Aggregate, Aggregation, Day, Direction, Variable};
+
+/// A test on the value of a variable.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Comparison {
+    Gt(f32),
+    Ge(f32),
+    Lt(f32),
+    Le(f32),
+    Eq(f32),
+    Ne(f32),
+    /// Between the bounds, included.
+    Within(f32, f32),
+}
+
+impl Comparison {
+    pub fn matches(&self, value: f32) -> bool {
+        match self {
+            Comparison::Gt(threshold) => value > *threshold,
+            Comparison::Ge(threshold) => value >= *threshold,
+            Comparison::Lt(threshold) => value < *threshold,
+            Comparison::Le(threshold) => value <= *threshold,
+            Comparison::Eq(threshold) => value == *threshold,
+            Comparison::Ne(threshold) => value != *threshold,
+            Comparison::Within(low, high) => (*low..=*high).contains(&value),
+        }
+    }
+}
+
+/// A predicate on a day, composable with [`Filter::and`], [`Filter::or`] and `!`.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Filter {
+    /// Every day.
+    All,
+    Between(RangeInclusive<Date>),
+    Years(RangeInclusive<i32>),
+    Months(Vec<Month>),
+    /// The days of the year from the first to the last, included. They are
+    /// counted on a leap year, from 1 to 366, so a calendar day always has the
+    /// same number. It wraps around the end of the year if the first is after
+    /// the last, like `(335, 60)` for the december to february winter.
+    DaysOfYear(u16, u16),
+    /// The days whose value of the variable passes the comparison, a missing value never does.
+    Value(Variable, Comparison),
+    /// The days with a dominant wind direction among these.
+    Directions(Vec<Direction>),
+    And(Box<Filter>, Box<Filter>),
+    Or(Box<Filter>, Box<Filter>),
+    Not(Box<Filter>),
+}
+
+impl Filter {
+    pub fn matches(&self, day: &Day) -> bool {
+        match self {
+            Filter::All => true,
+            Filter::Between(range) => range.contains(&day.date),
+            Filter::Years(years) => years.contains(&day.date.year()),
+            Filter::Months(months) => months.contains(&day.date.month()),
+            Filter::DaysOfYear(first, last) => {
+                let doy = day_of_year(day.date);
+                if first <= last {
+                    (*first..=*last).contains(&doy)
+                } else {
+                    doy >= *first || doy <= *last
+                }
+            }
+            Filter::Value(variable, comparison) => day
+                .get(*variable)
+                .is_some_and(|value| comparison.matches(value)),
+            Filter::Directions(directions) => day
+                .wind_direction
+                .is_some_and(|direction| directions.contains(&direction)),
+            Filter::And(left, right) => left.matches(day) && right.matches(day),
+            Filter::Or(left, right) => left.matches(day) || right.matches(day),
+            Filter::Not(filter) => !filter.matches(day),
+        }
+    }
+
+    pub fn and(self, other: Filter) -> Self {
+        Filter::And(Box::new(self), Box::new(other))
+    }
+
+    pub fn or(self, other: Filter) -> Self {
+        Filter::Or(Box::new(self), Box::new(other))
+    }
+}
+
+impl ops::Not for Filter {
+    type Output = Self;
+
+    fn not(self) -> Self {
+        Filter::Not(Box::new(self))
+    }
+}
+
+/// The days of an iterator matching a filter, see [`DaysQuery`].
+#[derive(Debug, Clone)]
+pub struct Query<I> {
+    days: I,
+    filter: Filter,
+}
+
+impl<'a, I: Iterator<Item = &'a Day>> Iterator for Query<I> {
+    type Item = &'a Day;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.days.by_ref().find(|day| self.filter.matches(day))
+    }
+}
+
+impl<'a, I: DoubleEndedIterator<Item = &'a Day>> DoubleEndedIterator for Query<I> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.days.by_ref().rfind(|day| self.filter.matches(day))
+    }
+}
+
+/// Filter any iterator of days, like [`Archive::days`](crate::Archive::days)
+/// or the days of a [`Report`](crate::Report).
+/// Like `archive.days().months([June, July]).where_(Variable::HighTemp, Gt(30.0))`.
+pub trait DaysQuery<'a>: Iterator<Item = &'a Day> + Sized {
+    /// Only keep the days matching `filter`.
+    fn matching(self, filter: Filter) -> Query<Self> {
+        Query { days: self, filter }
+    }
+
+    fn between(self, first: Date, last: Date) -> Query<Self> {
+        self.matching(Filter::Between(first..=last))
+    }
+
+    fn years(self, years: RangeInclusive<i32>) -> Query<Self> {
+        self.matching(Filter::Years(years))
+    }
+
+    fn months(self, months: impl IntoIterator<Item = Month>) -> Query<Self> {
+        self.matching(Filter::Months(months.into_iter().collect()))
+    }
+
+    /// See [`Filter::DaysOfYear`].
+    fn days_of_year(self, first: u16, last: u16) -> Query<Self> {
+        self.matching(Filter::DaysOfYear(first, last))
+    }
+
+    fn where_(self, variable: Variable, comparison: Comparison) -> Query<Self> {
+        self.matching(Filter::Value(variable, comparison))
+    }
+
+    fn directions(self, directions: impl IntoIterator<Item = Direction>) -> Query<Self> {
+        self.matching(Filter::Directions(directions.into_iter().collect()))
+    }
+
+    /// See [`aggregate`](crate::aggregate).
+    fn aggregate(self, variable: Variable, aggregation: Aggregation) -> Option<Aggregate> {
+        let days: Vec<&Day> = self.collect();
+        aggregate(&days, variable, aggregation)
+    }
+}
+
+impl<'a, I: Iterator<Item = &'a Day>> DaysQuery<'a> for I {}
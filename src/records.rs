@@ -0,0 +1,264 @@
+use std::collections::HashMap;
+
+use time::{Date, Month, PrimitiveDateTime};
+
+use crate::{Archive, Day, Variable};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Extreme {
+    Highest,
+    Lowest,
+}
+
+impl Extreme {
+    /// `true` if `left` is more extreme than `right`.
+    fn beats(&self, left: f32, right: f32) -> bool {
+        match self {
+            Extreme::Highest => left > right,
+            Extreme::Lowest => left < right,
+        }
+    }
+}
+
+/// Where a record applies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RecordScope {
+    AllTime,
+    /// Every day of a calendar month, all years included.
+    Month(Month),
+    /// A calendar day, all years included. Like the warmest 14 November.
+    CalendarDay(Month, u8),
+}
+
+impl RecordScope {
+    /// The scopes a date belongs to.
+    pub fn of(date: Date) -> [RecordScope; 3] {
+        [
+            RecordScope::AllTime,
+            RecordScope::Month(date.month()),
+            RecordScope::CalendarDay(date.month(), date.day()),
+        ]
+    }
+}
+
+/// A value reached on a day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Record {
+    pub value: f32,
+    pub date: Date,
+    /// When the value was reached if known, see [`Day::time_of`].
+    pub time: Option<PrimitiveDateTime>,
+}
+
+/// The most extreme values of a scope, the most extreme first.
+/// Ties are all kept and sorted by date.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Ranking {
+    pub entries: Vec<Record>,
+}
+
+impl Ranking {
+    /// The first day the record was reached.
+    pub fn record(&self) -> Option<&Record> {
+        self.entries.first()
+    }
+
+    /// Every day that reached the record.
+    pub fn ties(&self) -> &[Record] {
+        let Some(record) = self.record() else {
+            return &[];
+        };
+        let end = self
+            .entries
+            .iter()
+            .position(|entry| entry.value != record.value)
+            .unwrap_or(self.entries.len());
+        &self.entries[..end]
+    }
+
+    /// The entries that didn't reach the record.
+    pub fn runner_ups(&self) -> &[Record] {
+        &self.entries[self.ties().len()..]
+    }
+
+    fn insert(&mut self, record: Record, extreme: Extreme, depth: usize) {
+        if self.entries.iter().any(|entry| entry.date == record.date) {
+            return;
+        }
+        let idx = self.entries.partition_point(|entry| {
+            extreme.beats(entry.value, record.value)
+                || (entry.value == record.value && entry.date < record.date)
+        });
+        self.entries.insert(idx, record);
+
+        let mut distinct = 0;
+        let mut previous = None;
+        let end = self.entries.iter().position(|entry| {
+            if previous != Some(entry.value) {
+                distinct += 1;
+                previous = Some(entry.value);
+            }
+            distinct > depth
+        });
+        if let Some(end) = end {
+            self.entries.truncate(end);
+        }
+    }
+}
+
+/// A record broken or equalled by a new day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BrokenRecord {
+    pub variable: Variable,
+    pub extreme: Extreme,
+    pub scope: RecordScope,
+    pub new: Record,
+    /// `None` if there was no data for this scope from another day.
+    pub previous: Option<Record>,
+    /// `true` if the record was only equalled.
+    pub tied: bool,
+}
+
+/// The all-time, monthly and calendar day records of an archive.
+#[derive(Debug, Clone)]
+pub struct Records {
+    depth: usize,
+    rankings: HashMap<(Variable, Extreme, RecordScope), Ranking>,
+}
+
+impl Records {
+    /// Keeps the 3 most extreme distinct values of each ranking.
+    pub fn compute(archive: &Archive) -> Self {
+        Self::compute_with_depth(archive, 3)
+    }
+
+    /// Keeps the `depth` most extreme distinct values of each ranking.
+    pub fn compute_with_depth(archive: &Archive, depth: usize) -> Self {
+        let mut records = Self {
+            depth,
+            rankings: HashMap::new(),
+        };
+        for day in archive.days() {
+            records.insert(day);
+        }
+        records
+    }
+
+    /// The extremes tracked for each variable. The lowest rain or wind speed
+    /// are always zero and aren't tracked. The wind direction doesn't have records.
+    pub fn extremes(variable: Variable) -> &'static [Extreme] {
+        match variable {
+            Variable::MeanTemp | Variable::HighTemp | Variable::LowTemp => {
+                &[Extreme::Highest, Extreme::Lowest]
+            }
+            Variable::HeatDegDays
+            | Variable::CoolDegDays
+            | Variable::Rain
+            | Variable::AvgWindSpeed
+            | Variable::HighWindSpeed => &[Extreme::Highest],
+            Variable::WindDirection => &[],
+        }
+    }
+
+    pub fn ranking(
+        &self,
+        variable: Variable,
+        extreme: Extreme,
+        scope: RecordScope,
+    ) -> Option<&Ranking> {
+        self.rankings.get(&(variable, extreme, scope))
+    }
+
+    pub fn all_time(&self, variable: Variable, extreme: Extreme) -> Option<&Ranking> {
+        self.ranking(variable, extreme, RecordScope::AllTime)
+    }
+
+    pub fn monthly(&self, variable: Variable, extreme: Extreme, month: Month) -> Option<&Ranking> {
+        self.ranking(variable, extreme, RecordScope::Month(month))
+    }
+
+    /// The records of the calendar day of `date`, the year is ignored.
+    pub fn calendar_day(
+        &self,
+        variable: Variable,
+        extreme: Extreme,
+        date: Date,
+    ) -> Option<&Ranking> {
+        self.ranking(
+            variable,
+            extreme,
+            RecordScope::CalendarDay(date.month(), date.day()),
+        )
+    }
+
+    /// The records broken or equalled by the day, without updating the records.
+    pub fn check(&self, day: &Day) -> Vec<BrokenRecord> {
+        let mut broken = Vec::new();
+        for (variable, extreme, new) in Self::candidates(day) {
+            for scope in RecordScope::of(day.date) {
+                // The day may already be in the records, compare it to the other days
+                let previous = self
+                    .ranking(variable, extreme, scope)
+                    .and_then(|ranking| {
+                        ranking
+                            .entries
+                            .iter()
+                            .find(|previous| previous.date != day.date)
+                    })
+                    .copied();
+                let (beaten, tied) = match previous {
+                    Some(previous) => (
+                        extreme.beats(new.value, previous.value),
+                        new.value == previous.value,
+                    ),
+                    None => (true, false),
+                };
+                if beaten || tied {
+                    broken.push(BrokenRecord {
+                        variable,
+                        extreme,
+                        scope,
+                        new,
+                        previous,
+                        tied,
+                    });
+                }
+            }
+        }
+        broken
+    }
+
+    /// Add the days to the records and returns the records they broke or equalled.
+    pub fn ingest<'a>(&mut self, days: impl IntoIterator<Item = &'a Day>) -> Vec<BrokenRecord> {
+        let mut broken = Vec::new();
+        for day in days {
+            broken.extend(self.check(day));
+            self.insert(day);
+        }
+        broken
+    }
+
+    fn insert(&mut self, day: &Day) {
+        for (variable, extreme, record) in Self::candidates(day) {
+            for scope in RecordScope::of(day.date) {
+                self.rankings
+                    .entry((variable, extreme, scope))
+                    .or_default()
+                    .insert(record, extreme, self.depth);
+            }
+        }
+    }
+
+    fn candidates(day: &Day) -> impl Iterator<Item = (Variable, Extreme, Record)> + '_ {
+        Variable::ALL.into_iter().flat_map(move |variable| {
+            let record = day.get(variable).map(|value| Record {
+                value,
+                date: day.date,
+                time: day.time_of(variable),
+            });
+            Self::extremes(variable)
+                .iter()
+                .filter_map(move |extreme| Some((variable, *extreme, record?)))
+        })
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::BTreeMap;
+
+use time::{Date, Duration, Month, PrimitiveDateTime};
+
+use crate::{last_day_of_month, Aggregation, Coverage, Day, Variable};
+
+/// The length of the periods the days are aggregated to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Period {
+    /// ISO weeks, starting on monday.
+    Week,
+    Month,
+    /// Meteorological seasons, see [`Season`].
+    Season,
+    /// A year starting on the first day of the given month, usually october.
+    HydrologicalYear(Month),
+    Year,
+}
+
+/// Meteorological seasons of the northern hemisphere.
+/// The winter starts in december and belongs to the year of its january.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Season {
+    /// December, January, February
+    Winter,
+    /// March, April, May
+    Spring,
+    /// June, July, August
+    Summer,
+    /// September, October, November
+    Autumn,
+}
+
+impl Season {
+    pub const ALL: [Season; 4] = [
+        Season::Winter,
+        Season::Spring,
+        Season::Summer,
+        Season::Autumn,
+    ];
+
+    pub fn of(month: Month) -> Self {
+        match month {
+            Month::December | Month::January | Month::February => Season::Winter,
+            Month::March | Month::April | Month::May => Season::Spring,
+            Month::June | Month::July | Month::August => Season::Summer,
+            Month::September | Month::October | Month::November => Season::Autumn,
+        }
+    }
+
+    pub fn first_month(&self) -> Month {
+        match self {
+            Season::Winter => Month::December,
+            Season::Spring => Month::March,
+            Season::Summer => Month::June,
+            Season::Autumn => Month::September,
+        }
+    }
+
+    pub fn months(&self) -> [Month; 3] {
+        let first = self.first_month();
+        [first, first.next(), first.next().next()]
+    }
+}
+
+impl Period {
+    /// First day of the period containing `date`.
+    pub fn start_of(&self, date: Date) -> Date {
+        match self {
+            Period::Week => date - Duration::days(date.weekday().number_days_from_monday() as i64),
+            Period::Month => date.replace_day(1).unwrap(),
+            Period::Season => {
+                let first = Season::of(date.month()).first_month();
+                let year = if (date.month() as u8) < first as u8 {
+                    date.year() - 1
+                } else {
+                    date.year()
+                };
+                Date::from_calendar_date(year, first, 1).unwrap()
+            }
+            Period::HydrologicalYear(first) => {
+                let year = if (date.month() as u8) < *first as u8 {
+                    date.year() - 1
+                } else {
+                    date.year()
+                };
+                Date::from_calendar_date(year, *first, 1).unwrap()
+            }
+            Period::Year => Date::from_calendar_date(date.year(), Month::January, 1).unwrap(),
+        }
+    }
+
+    /// Last day of the period containing `date`.
+    pub fn end_of(&self, date: Date) -> Date {
+        self.next_start(date).previous_day().unwrap()
+    }
+
+    /// First day of the period following the one containing `date`.
+    pub fn next_start(&self, date: Date) -> Date {
+        let start = self.start_of(date);
+        match self {
+            Period::Week => start + Duration::days(7),
+            Period::Month => add_months(start, 1),
+            Period::Season => add_months(start, 3),
+            Period::HydrologicalYear(_) | Period::Year => add_months(start, 12),
+        }
+    }
+}
+
+fn add_months(mut date: Date, months: u8) -> Date {
+    for _ in 0..months {
+        date = last_day_of_month(date).next_day().unwrap();
+    }
+    date
+}
+
+/// Aggregate days over a [`Period`], each variable with its own [`Aggregation`].
+#[derive(Debug, Clone)]
+pub struct Resampler {
+    period: Period,
+    min_coverage: f32,
+    aggregations: BTreeMap<Variable, Aggregation>,
+}
+
+/// The aggregated value of a variable over a period.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Aggregate {
+    pub value: f32,
+    /// Number of days with a value for the variable.
+    pub count: usize,
+    /// For [`Aggregation::Max`] and [`Aggregation::Min`], the first day the extreme was reached.
+    pub date: Option<Date>,
+    /// For [`Aggregation::Max`] and [`Aggregation::Min`], when the extreme was reached if known.
+    pub time: Option<PrimitiveDateTime>,
+}
+
+/// The aggregates of a single period.
+#[derive(Debug, Clone)]
+pub struct Resampled {
+    pub start: Date,
+    /// Inclusive
+    pub end: Date,
+    pub coverage: Coverage,
+    /// `false` if the coverage is below the threshold of the [`Resampler`].
+    pub complete: bool,
+    /// Variables without any value over the period are missing.
+    pub values: BTreeMap<Variable, Aggregate>,
+}
+
+impl Resampled {
+    pub fn get(&self, variable: Variable) -> Option<&Aggregate> {
+        self.values.get(&variable)
+    }
+}
+
+impl Resampler {
+    /// Every variable uses its default aggregation and a period must be covered at 90% to be complete.
+    pub fn new(period: Period) -> Self {
+        Self {
+            period,
+            min_coverage: 90.0,
+            aggregations: Variable::ALL
+                .into_iter()
+                .map(|variable| (variable, variable.aggregation()))
+                .collect(),
+        }
+    }
+
+    /// Minimum percentage of present days for a period to be complete.
+    pub fn min_coverage(mut self, percentage: f32) -> Self {
+        self.min_coverage = percentage;
+        self
+    }
+
+    /// Override the default aggregation of a variable.
+    pub fn aggregation(mut self, variable: Variable, aggregation: Aggregation) -> Self {
+        self.aggregations.insert(variable, aggregation);
+        self
+    }
+
+    /// Only aggregate these variables.
+    pub fn variables(mut self, variables: impl IntoIterator<Item = Variable>) -> Self {
+        self.aggregations = variables
+            .into_iter()
+            .map(|variable| {
+                let aggregation = self
+                    .aggregations
+                    .get(&variable)
+                    .copied()
+                    .unwrap_or(variable.aggregation());
+                (variable, aggregation)
+            })
+            .collect();
+        self
+    }
+
+    /// Returns every period from the one of the first day to the one of the last day,
+    /// including the periods without any day.
+    pub fn resample<'a>(&self, days: impl IntoIterator<Item = &'a Day>) -> Vec<Resampled> {
+        let mut periods: BTreeMap<Date, Vec<&Day>> = BTreeMap::new();
+        for day in days {
+            periods
+                .entry(self.period.start_of(day.date))
+                .or_default()
+                .push(day);
+        }
+
+        let (Some(first), Some(last)) = (
+            periods.keys().next().copied(),
+            periods.keys().next_back().copied(),
+        ) else {
+            return Vec::new();
+        };
+
+        let mut ret = Vec::new();
+        let mut start = first;
+        while start <= last {
+            let days = periods.remove(&start).unwrap_or_default();
+            ret.push(self.aggregate(start, &days));
+            start = self.period.next_start(start);
+        }
+        ret
+    }
+
+    fn aggregate(&self, start: Date, days: &[&Day]) -> Resampled {
+        let end = self.period.end_of(start);
+        let mut present: Vec<Date> = days.iter().map(|day| day.date).collect();
+        present.sort_unstable();
+        present.dedup();
+        let coverage = Coverage {
+            expected: (end - start).whole_days() as usize + 1,
+            present: present.len(),
+        };
+
+        let values = self
+            .aggregations
+            .iter()
+            .filter_map(|(variable, aggregation)| {
+                Some((*variable, aggregate(days, *variable, *aggregation)?))
+            })
+            .collect();
+
+        Resampled {
+            start,
+            end,
+            coverage,
+            complete: coverage.percentage() >= self.min_coverage,
+            values,
+        }
+    }
+}
+
+/// Aggregate the values of a variable, `None` if no day has a value.
+pub fn aggregate(days: &[&Day], variable: Variable, aggregation: Aggregation) -> Option<Aggregate> {
+    let values: Vec<(&Day, f32)> = days
+        .iter()
+        .filter_map(|day| Some((*day, day.get(variable)?)))
+        .collect();
+    if values.is_empty() {
+        return None;
+    }
+    let count = values.len();
+    let plain = |value| Aggregate {
+        value,
+        count,
+        date: None,
+        time: None,
+    };
+    let extreme = |(day, value): (&Day, f32)| Aggregate {
+        value,
+        count,
+        date: Some(day.date),
+        time: day.time_of(variable),
+    };
+
+    let aggregate = match aggregation {
+        Aggregation::Sum => plain(values.iter().map(|(_, value)| value).sum()),
+        Aggregation::Mean => {
+            plain(values.iter().map(|(_, value)| value).sum::<f32>() / count as f32)
+        }
+        Aggregation::Max => {
+            extreme(
+                values
+                    .iter()
+                    .copied()
+                    .reduce(|max, v| if v.1 > max.1 { v } else { max })?,
+            )
+        }
+        Aggregation::Min => {
+            extreme(
+                values
+                    .iter()
+                    .copied()
+                    .reduce(|min, v| if v.1 < min.1 { v } else { min })?,
+            )
+        }
+        Aggregation::Mode => {
+            let mut sorted: Vec<f32> = values.iter().map(|(_, value)| *value).collect();
+            sorted.sort_by(f32::total_cmp);
+            let mut mode = (sorted[0], 0);
+            for run in sorted.chunk_by(|left, right| left == right) {
+                if run.len() > mode.1 {
+                    mode = (run[0], run.len());
+                }
+            }
+            plain(mode.0)
+        }
+        Aggregation::VectorMean => {
+            let (sin, cos) = values.iter().fold((0.0, 0.0), |(sin, cos), (_, value)| {
+                let angle = value.to_radians();
+                (sin + angle.sin(), cos + angle.cos())
+            });
+            plain(sin.atan2(cos).to_degrees().rem_euclid(360.0))
+        }
+    };
+
+    Some(aggregate)
+}
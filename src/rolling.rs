@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use time::{Date, Duration};
+
+use crate::{aggregate, Aggregation, Day, Variable};
+
+/// Where a window sits relative to the day its value is given to.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Alignment {
+    /// The window ends on the day, like the 5 days rain up to a day.
+    #[default]
+    Trailing,
+    /// The window is centered on the day, better to smooth a curve.
+    /// With an even length the window has one more day after the day.
+    Centered,
+}
+
+/// Statistics over windows of consecutive calendar days.
+///
+/// A window never slides across missing days: it only has a value if at most
+/// [`RollingWindow::max_missing`] of its days are missing or without value.
+/// The windows going beyond the first or the last day don't have a value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RollingWindow {
+    length: usize,
+    alignment: Alignment,
+    max_missing: usize,
+}
+
+impl RollingWindow {
+    /// A trailing window of `length` days without missing day.
+    pub fn new(length: usize) -> Self {
+        Self {
+            length: length.max(1),
+            alignment: Alignment::default(),
+            max_missing: 0,
+        }
+    }
+
+    pub fn alignment(mut self, alignment: Alignment) -> Self {
+        self.alignment = alignment;
+        self
+    }
+
+    /// Number of missing days a window can have and still get a value, 0 by default.
+    pub fn max_missing(mut self, max_missing: usize) -> Self {
+        self.max_missing = max_missing;
+        self
+    }
+
+    pub fn mean<'a>(
+        &self,
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<Date, Option<f32>> {
+        self.aggregate(variable, Aggregation::Mean, days)
+    }
+
+    pub fn sum<'a>(
+        &self,
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<Date, Option<f32>> {
+        self.aggregate(variable, Aggregation::Sum, days)
+    }
+
+    pub fn min<'a>(
+        &self,
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<Date, Option<f32>> {
+        self.aggregate(variable, Aggregation::Min, days)
+    }
+
+    pub fn max<'a>(
+        &self,
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<Date, Option<f32>> {
+        self.aggregate(variable, Aggregation::Max, days)
+    }
+
+    /// The value of the window of every day from the first to the last day.
+    /// Use [`Aggregation::VectorMean`] to average [`Variable::WindDirection`].
+    pub fn aggregate<'a>(
+        &self,
+        variable: Variable,
+        aggregation: Aggregation,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<Date, Option<f32>> {
+        let days = calendar(variable, days);
+        let (before, after) = match self.alignment {
+            Alignment::Trailing => (self.length - 1, 0),
+            Alignment::Centered => ((self.length - 1) / 2, self.length / 2),
+        };
+
+        days.iter()
+            .enumerate()
+            .map(|(i, (date, _))| {
+                let window = i
+                    .checked_sub(before)
+                    .and_then(|start| days.get(start..=i + after));
+                let value = window.and_then(|window| {
+                    let present: Vec<&Day> = window.iter().filter_map(|(_, day)| *day).collect();
+                    if window.len() - present.len() > self.max_missing {
+                        return None;
+                    }
+                    Some(aggregate(&present, variable, aggregation)?.value)
+                });
+                (*date, value)
+            })
+            .collect()
+    }
+}
+
+/// Exponentially weighted mean of every day from the first to the last day,
+/// `alpha` between 0 and 1 being the weight of the last day.
+/// A missing day has no value and the mean restarts from the next day.
+pub fn ewma<'a>(
+    variable: Variable,
+    alpha: f32,
+    days: impl IntoIterator<Item = &'a Day>,
+) -> BTreeMap<Date, Option<f32>> {
+    let mut mean = None;
+    calendar(variable, days)
+        .into_iter()
+        .map(|(date, day)| {
+            mean = day
+                .and_then(|day| day.get(variable))
+                .map(|value| match mean {
+                    Some(mean) => alpha * value + (1.0 - alpha) * mean,
+                    None => value,
+                });
+            (date, mean)
+        })
+        .collect()
+}
+
+/// Every date from the first to the last day, with its day if it has a value.
+fn calendar<'a>(
+    variable: Variable,
+    days: impl IntoIterator<Item = &'a Day>,
+) -> Vec<(Date, Option<&'a Day>)> {
+    let days: BTreeMap<Date, &Day> = days
+        .into_iter()
+        .filter(|day| day.get(variable).is_some())
+        .map(|day| (day.date, day))
+        .collect();
+    let (Some(first), Some(last)) = (
+        days.keys().next().copied(),
+        days.keys().next_back().copied(),
+    ) else {
+        return Vec::new();
+    };
+    (0..=(last - first).whole_days())
+        .map(|offset| {
+            let date = first + Duration::days(offset);
+            (date, days.get(&date).copied())
+        })
+        .collect()
+}
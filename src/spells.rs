@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+
+use time::Date;
+
+use crate::{Day, Stats, Variable};
+
+/// What to do with the missing days in the middle of a spell.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum GapPolicy {
+    /// A missing day ends the spell.
+    #[default]
+    Break,
+    /// Up to this many consecutive missing days are considered part of the spell.
+    Bridge(usize),
+}
+
+/// A run of consecutive days matching a predicate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Spell {
+    pub start: Date,
+    /// Inclusive
+    pub end: Date,
+    /// Number of calendar days from the start to the end, the bridged days included.
+    pub length: usize,
+    /// Number of bridged days, see [`GapPolicy::Bridge`].
+    pub missing: usize,
+    /// Statistics of the intensity variable over the days of the spell.
+    pub intensity: Option<Stats>,
+}
+
+/// Find the spells of days matching a predicate.
+#[derive(Debug, Clone)]
+pub struct SpellFinder<F> {
+    predicate: F,
+    min_length: usize,
+    gaps: GapPolicy,
+    intensity: Option<Variable>,
+}
+
+impl SpellFinder<fn(&Day) -> bool> {
+    /// Days with less than 0.2 mm of rain.
+    pub fn dry() -> Self {
+        SpellFinder::new((|day| day.rain < 0.2) as fn(&Day) -> bool).intensity(Variable::Rain)
+    }
+
+    /// Days with at least 0.2 mm of rain.
+    pub fn wet() -> Self {
+        SpellFinder::new((|day| day.rain >= 0.2) as fn(&Day) -> bool).intensity(Variable::Rain)
+    }
+
+    /// Days with a low of 0 ºC or less.
+    pub fn frost() -> Self {
+        SpellFinder::new((|day| day.low_temp <= 0.0) as fn(&Day) -> bool)
+            .intensity(Variable::LowTemp)
+    }
+
+    /// At least three days in a row with a high of 30 ºC or more.
+    pub fn heatwave() -> Self {
+        SpellFinder::new((|day| day.high_temp >= 30.0) as fn(&Day) -> bool)
+            .min_length(3)
+            .intensity(Variable::HighTemp)
+    }
+}
+
+impl<F: Fn(&Day) -> bool> SpellFinder<F> {
+    /// Spells of at least one day, broken by the missing days, without intensity.
+    pub fn new(predicate: F) -> Self {
+        Self {
+            predicate,
+            min_length: 1,
+            gaps: GapPolicy::default(),
+            intensity: None,
+        }
+    }
+
+    /// Minimum number of calendar days of a spell.
+    pub fn min_length(mut self, days: usize) -> Self {
+        self.min_length = days;
+        self
+    }
+
+    pub fn gaps(mut self, gaps: GapPolicy) -> Self {
+        self.gaps = gaps;
+        self
+    }
+
+    /// The variable to compute the statistics of over each spell.
+    pub fn intensity(mut self, variable: Variable) -> Self {
+        self.intensity = Some(variable);
+        self
+    }
+
+    /// The spells sorted by date.
+    pub fn find<'a>(&self, days: impl IntoIterator<Item = &'a Day>) -> Vec<Spell> {
+        let mut days: Vec<&Day> = days.into_iter().collect();
+        days.sort_by_key(|day| day.date);
+        days.dedup_by_key(|day| day.date);
+
+        let mut spells = Vec::new();
+        let mut current: Vec<&Day> = Vec::new();
+        for day in days {
+            if !(self.predicate)(day) {
+                self.close(&mut current, &mut spells);
+                continue;
+            }
+            if let Some(last) = current.last() {
+                let missing = (day.date - last.date).whole_days() as usize - 1;
+                let bridged = match self.gaps {
+                    GapPolicy::Break => missing == 0,
+                    GapPolicy::Bridge(max) => missing <= max,
+                };
+                if !bridged {
+                    self.close(&mut current, &mut spells);
+                }
+            }
+            current.push(day);
+        }
+        self.close(&mut current, &mut spells);
+
+        spells
+    }
+
+    /// The longest spell starting each year, the first one on ties.
+    pub fn longest_per_year<'a>(
+        &self,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<i32, Spell> {
+        let mut longest: BTreeMap<i32, Spell> = BTreeMap::new();
+        for spell in self.find(days) {
+            let year = spell.start.year();
+            if longest
+                .get(&year)
+                .is_none_or(|longest| spell.length > longest.length)
+            {
+                longest.insert(year, spell);
+            }
+        }
+        longest
+    }
+
+    fn close(&self, current: &mut Vec<&Day>, spells: &mut Vec<Spell>) {
+        let (Some(first), Some(last)) = (current.first(), current.last()) else {
+            return;
+        };
+        let length = (last.date - first.date).whole_days() as usize + 1;
+        if length >= self.min_length {
+            let intensity = self.intensity.and_then(|variable| {
+                let values: Vec<f32> = current.iter().filter_map(|day| day.get(variable)).collect();
+                Stats::new(&values)
+            });
+            spells.push(Spell {
+                start: first.date,
+                end: last.date,
+                length,
+                missing: length - current.len(),
+                intensity,
+            });
+        }
+        current.clear();
+    }
+}
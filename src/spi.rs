@@ -0,0 +1,187 @@
+use std::{collections::BTreeMap, fmt, ops::RangeInclusive};
+
+use time::Date;
+
+use crate::{
+    distribution::{Distribution, Gamma, ZeroInflated},
+    stats::normal_quantile,
+    Day, Period, Resampler, Variable,
+};
+
+/// The classification of McKee et al. (1993).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DroughtCategory {
+    /// SPI of 2 and above.
+    ExtremelyWet,
+    /// SPI from 1.5 to 2.
+    VeryWet,
+    /// SPI from 1 to 1.5.
+    ModeratelyWet,
+    /// SPI from -1 to 1.
+    NearNormal,
+    /// SPI from -1.5 to -1.
+    ModeratelyDry,
+    /// SPI from -2 to -1.5.
+    SeverelyDry,
+    /// SPI of -2 and below.
+    ExtremelyDry,
+}
+
+impl DroughtCategory {
+    pub fn of(spi: f32) -> Self {
+        match spi {
+            spi if spi >= 2.0 => DroughtCategory::ExtremelyWet,
+            spi if spi >= 1.5 => DroughtCategory::VeryWet,
+            spi if spi >= 1.0 => DroughtCategory::ModeratelyWet,
+            spi if spi > -1.0 => DroughtCategory::NearNormal,
+            spi if spi > -1.5 => DroughtCategory::ModeratelyDry,
+            spi if spi > -2.0 => DroughtCategory::SeverelyDry,
+            _ => DroughtCategory::ExtremelyDry,
+        }
+    }
+}
+
+impl fmt::Display for DroughtCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            DroughtCategory::ExtremelyWet => "extremely wet",
+            DroughtCategory::VeryWet => "very wet",
+            DroughtCategory::ModeratelyWet => "moderately wet",
+            DroughtCategory::NearNormal => "near normal",
+            DroughtCategory::ModeratelyDry => "moderately dry",
+            DroughtCategory::SeverelyDry => "severely dry",
+            DroughtCategory::ExtremelyDry => "extremely dry",
+        };
+        f.write_str(name)
+    }
+}
+
+/// The SPI of the window ending on a month.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpiValue {
+    /// First day of the last month of the window.
+    pub month: Date,
+    /// Rain over the window, `None` if a month of the window is missing or incomplete.
+    pub rain: Option<f32>,
+    /// `None` if there is no rain or if its calendar month couldn't be fitted.
+    pub spi: Option<f32>,
+}
+
+impl SpiValue {
+    pub fn category(&self) -> Option<DroughtCategory> {
+        self.spi.map(DroughtCategory::of)
+    }
+}
+
+/// Standardized Precipitation Index over windows of `scale` months.
+///
+/// The rain of the windows ending on the same calendar month is fitted by a
+/// gamma distribution with a probability of zero rain, then projected on the
+/// standard normal distribution.
+#[derive(Debug, Clone)]
+pub struct Spi {
+    scale: usize,
+    min_coverage: f32,
+    calibration: Option<RangeInclusive<i32>>,
+}
+
+impl Spi {
+    /// The usual scales are 1, 3, 6 and 12 months.
+    pub fn new(scale: usize) -> Self {
+        Self {
+            scale: scale.max(1),
+            min_coverage: 90.0,
+            calibration: None,
+        }
+    }
+
+    /// Minimum percentage of present days for a month to be used, 90% by default.
+    pub fn min_coverage(mut self, percentage: f32) -> Self {
+        self.min_coverage = percentage;
+        self
+    }
+
+    /// Only fit the distributions on the windows ending in these years.
+    /// By default every year is used.
+    pub fn calibration(mut self, years: RangeInclusive<i32>) -> Self {
+        self.calibration = Some(years);
+        self
+    }
+
+    /// The fitted distribution of each calendar month, indexed from 1 to 12.
+    pub fn fit<'a>(
+        &self,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<u8, ZeroInflated<Gamma>> {
+        self.fit_windows(&self.windows(days))
+    }
+
+    /// The SPI of every month from the first to the last day.
+    pub fn compute<'a>(&self, days: impl IntoIterator<Item = &'a Day>) -> Vec<SpiValue> {
+        let windows = self.windows(days);
+        let distributions = self.fit_windows(&windows);
+        windows
+            .into_iter()
+            .map(|(month, rain)| {
+                let spi = rain.and_then(|rain| {
+                    let distribution = distributions.get(&(month.month() as u8))?;
+                    // Keep the result finite, ±3.09 are the 0.1% and 99.9% quantiles
+                    let spi = normal_quantile(distribution.cdf(rain as f64)).clamp(-3.09, 3.09);
+                    Some(spi as f32)
+                });
+                SpiValue { month, rain, spi }
+            })
+            .collect()
+    }
+
+    /// Rain over the window ending on each month.
+    fn windows<'a>(&self, days: impl IntoIterator<Item = &'a Day>) -> Vec<(Date, Option<f32>)> {
+        let months: Vec<(Date, Option<f32>)> = Resampler::new(Period::Month)
+            .variables([Variable::Rain])
+            .min_coverage(self.min_coverage)
+            .resample(days)
+            .iter()
+            .map(|month| {
+                let rain = month
+                    .complete
+                    .then(|| month.get(Variable::Rain).map(|rain| rain.value))
+                    .flatten();
+                (month.start, rain)
+            })
+            .collect();
+
+        months
+            .iter()
+            .enumerate()
+            .map(|(i, (month, _))| {
+                let rain = i.checked_sub(self.scale - 1).and_then(|first| {
+                    months[first..=i]
+                        .iter()
+                        .map(|(_, rain)| *rain)
+                        .sum::<Option<f32>>()
+                });
+                (*month, rain)
+            })
+            .collect()
+    }
+
+    fn fit_windows(&self, windows: &[(Date, Option<f32>)]) -> BTreeMap<u8, ZeroInflated<Gamma>> {
+        let mut samples: BTreeMap<u8, Vec<f64>> = BTreeMap::new();
+        for (month, rain) in windows {
+            let calibrated = self
+                .calibration
+                .as_ref()
+                .is_none_or(|years| years.contains(&month.year()));
+            if let (Some(rain), true) = (rain, calibrated) {
+                samples
+                    .entry(month.month() as u8)
+                    .or_default()
+                    .push(*rain as f64);
+            }
+        }
+        samples
+            .into_iter()
+            .filter_map(|(month, values)| Some((month, ZeroInflated::fit(&values)?)))
+            .collect()
+    }
+}
@@ -0,0 +1,82 @@
+use crate::{aggregate, Aggregate, Aggregation, Day, Direction, Report, Variable};
+
+/// The figures of the totals row and footer of a monthly report, computed
+/// from the days so it works on any period and when the totals row is wrong.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Summary {
+    /// Number of days the summary was computed on.
+    pub days: usize,
+
+    pub mean_temp: f32,
+    /// First day the highest high was reached.
+    pub high_temp: Aggregate,
+    /// First day the lowest low was reached.
+    pub low_temp: Aggregate,
+
+    pub heat_deg_days: f32,
+    pub cool_deg_days: f32,
+
+    pub rain: f32,
+    /// First day with the most rain.
+    pub max_rain: Aggregate,
+    /// Days with at least 0.2 mm of rain.
+    pub rain_days_0_2: usize,
+    /// Days with at least 2 mm of rain.
+    pub rain_days_2: usize,
+    /// Days with at least 20 mm of rain.
+    pub rain_days_20: usize,
+
+    /// Days with a high of at least 32 ºC.
+    pub max_ge_32: usize,
+    /// Days with a high of 0 ºC or less.
+    pub max_le_0: usize,
+    /// Days with a low of 0 ºC or less.
+    pub min_le_0: usize,
+    /// Days with a low of -18 ºC or less.
+    pub min_le_minus_18: usize,
+
+    pub avg_wind_speed: f32,
+    /// First day with the strongest gust.
+    pub high_wind_speed: Aggregate,
+    /// The most frequent direction among the days, `None` if no day has one.
+    pub dominant_direction: Option<Direction>,
+}
+
+impl Summary {
+    /// `None` if there is no day.
+    pub fn compute(days: &[Day]) -> Option<Self> {
+        let days: Vec<&Day> = days.iter().collect();
+        let sum = |variable| Some(aggregate(&days, variable, Aggregation::Sum)?.value);
+        let mean = |variable| Some(aggregate(&days, variable, Aggregation::Mean)?.value);
+        let count = |f: fn(&Day) -> bool| days.iter().filter(|day| f(day)).count();
+
+        Some(Self {
+            days: days.len(),
+            mean_temp: mean(Variable::MeanTemp)?,
+            high_temp: aggregate(&days, Variable::HighTemp, Aggregation::Max)?,
+            low_temp: aggregate(&days, Variable::LowTemp, Aggregation::Min)?,
+            heat_deg_days: sum(Variable::HeatDegDays)?,
+            cool_deg_days: sum(Variable::CoolDegDays)?,
+            rain: sum(Variable::Rain)?,
+            max_rain: aggregate(&days, Variable::Rain, Aggregation::Max)?,
+            rain_days_0_2: count(|day| day.rain >= 0.2),
+            rain_days_2: count(|day| day.rain >= 2.0),
+            rain_days_20: count(|day| day.rain >= 20.0),
+            max_ge_32: count(|day| day.high_temp >= 32.0),
+            max_le_0: count(|day| day.high_temp <= 0.0),
+            min_le_0: count(|day| day.low_temp <= 0.0),
+            min_le_minus_18: count(|day| day.low_temp <= -18.0),
+            avg_wind_speed: mean(Variable::AvgWindSpeed)?,
+            high_wind_speed: aggregate(&days, Variable::HighWindSpeed, Aggregation::Max)?,
+            dominant_direction: aggregate(&days, Variable::WindDirection, Aggregation::Mode)
+                .map(|mode| Direction::from_degrees(mode.value)),
+        })
+    }
+}
+
+impl Report {
+    /// `None` if the report doesn't contain any day.
+    pub fn summary(&self) -> Option<Summary> {
+        Summary::compute(&self.days)
+    }
+}
@@ -0,0 +1,165 @@
+use std::collections::BTreeMap;
+
+use time::{PrimitiveDateTime, Time};
+
+use crate::{Day, Season, Variable};
+
+/// Number of half hours in a day.
+const SLOTS: usize = 48;
+
+impl Day {
+    /// When the value was reached in minutes since the start of the day, from 1 to 1440.
+    ///
+    /// The station stamps a value with the end of its half hour, the last half
+    /// hour of the day being stamped `00:00` on the same day. It's returned as
+    /// 1440, the end of the day, instead of 0.
+    pub fn minute_of_day(&self, variable: Variable) -> Option<u16> {
+        let time = self.time_of(variable)?;
+        Some(match time.hour() as u16 * 60 + time.minute() as u16 {
+            0 if time.date() == self.date => 1440,
+            minute => minute,
+        })
+    }
+}
+
+/// When the extreme of a variable happens during the day, counted by half hour.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimeOfDay {
+    pub variable: Variable,
+    /// The slot `i` counts the values stamped between `i * 30` excluded and
+    /// `(i + 1) * 30` minutes included, see [`Day::minute_of_day`].
+    pub counts: [usize; SLOTS],
+}
+
+/// A day whose extreme happened at a rare time, like a high during the night
+/// brought by a front.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct UnusualTime {
+    pub variable: Variable,
+    pub value: f32,
+    pub time: PrimitiveDateTime,
+    /// The percentage of the days of the distribution with an extreme around this half hour.
+    pub frequency: f32,
+}
+
+impl TimeOfDay {
+    /// Only [`Variable::HighTemp`], [`Variable::LowTemp`] and
+    /// [`Variable::HighWindSpeed`] are timestamped, the other variables are empty.
+    pub fn new<'a>(variable: Variable, days: impl IntoIterator<Item = &'a Day>) -> Self {
+        let mut counts = [0; SLOTS];
+        for minute in days
+            .into_iter()
+            .filter_map(|day| day.minute_of_day(variable))
+        {
+            counts[Self::slot_of(minute)] += 1;
+        }
+        Self { variable, counts }
+    }
+
+    /// One distribution per calendar month, indexed from 1 to 12.
+    pub fn by_month<'a>(
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<u8, Self> {
+        Self::group(variable, days, |day| day.date.month() as u8)
+    }
+
+    pub fn by_season<'a>(
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+    ) -> BTreeMap<Season, Self> {
+        Self::group(variable, days, |day| Season::of(day.date.month()))
+    }
+
+    fn group<'a, K: Ord>(
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+        key: impl Fn(&Day) -> K,
+    ) -> BTreeMap<K, Self> {
+        let mut groups: BTreeMap<K, Vec<&Day>> = BTreeMap::new();
+        for day in days {
+            groups.entry(key(day)).or_default().push(day);
+        }
+        groups
+            .into_iter()
+            .map(|(key, days)| (key, Self::new(variable, days)))
+            .collect()
+    }
+
+    /// The slot of a minute returned by [`Day::minute_of_day`].
+    pub fn slot_of(minute: u16) -> usize {
+        (minute.clamp(1, 1440) as usize - 1) / 30
+    }
+
+    /// The stamp closing a slot, the last one being `00:00`.
+    pub fn slot_end(slot: usize) -> Time {
+        let minutes = (slot + 1) * 30 % 1440;
+        Time::from_hms((minutes / 60) as u8, (minutes % 60) as u8, 0).unwrap()
+    }
+
+    pub fn total(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    /// The percentage of the values in a slot.
+    pub fn frequency(&self, slot: usize) -> f32 {
+        self.counts[slot] as f32 * 100.0 / self.total().max(1) as f32
+    }
+
+    /// The mean percentage of a slot and its two neighbours, less noisy than
+    /// [`TimeOfDay::frequency`] on a few years.
+    pub fn smoothed_frequency(&self, slot: usize) -> f32 {
+        [slot + SLOTS - 1, slot, slot + 1]
+            .into_iter()
+            .map(|slot| self.frequency(slot % SLOTS))
+            .sum::<f32>()
+            / 3.0
+    }
+
+    /// The most frequent slot, the earliest one on ties. `None` if there is no value.
+    pub fn peak(&self) -> Option<usize> {
+        (self.total() != 0).then(|| {
+            (0..SLOTS)
+                .rev()
+                .max_by_key(|slot| self.counts[*slot])
+                .unwrap()
+        })
+    }
+
+    /// The days whose extreme happened in a slot holding less than `threshold`
+    /// percent of the values of this distribution, see [`TimeOfDay::smoothed_frequency`].
+    pub fn unusual<'a>(
+        &self,
+        days: impl IntoIterator<Item = &'a Day>,
+        threshold: f32,
+    ) -> Vec<UnusualTime> {
+        days.into_iter()
+            .filter_map(|day| {
+                let slot = Self::slot_of(day.minute_of_day(self.variable)?);
+                let frequency = self.smoothed_frequency(slot);
+                if frequency >= threshold {
+                    return None;
+                }
+                Some(UnusualTime {
+                    variable: self.variable,
+                    value: day.get(self.variable)?,
+                    time: day.time_of(self.variable)?,
+                    frequency,
+                })
+            })
+            .collect()
+    }
+
+    /// [`TimeOfDay::unusual`] with every day compared to the distribution of its calendar month.
+    pub fn unusual_by_month<'a>(
+        variable: Variable,
+        days: impl IntoIterator<Item = &'a Day>,
+        threshold: f32,
+    ) -> Vec<UnusualTime> {
+        let days: Vec<&Day> = days.into_iter().collect();
+        let months = Self::by_month(variable, days.iter().copied());
+        days.into_iter()
+            .flat_map(|day| months[&(day.date.month() as u8)].unusual([day], threshold))
+            .collect()
+    }
+}
@@ -0,0 +1,138 @@
+use std::collections::BTreeMap;
+
+use crate::stats::{normal_cdf, student_t_quantile};
+
+/// Linear trend of a series and its significance.
+/// The slopes are expressed per decade, the `x` of the series being in years.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Trend {
+    /// Number of points of the series.
+    pub n: usize,
+    /// Slope of the ordinary least squares regression.
+    pub ols_slope: f64,
+    pub ols_intercept: f64,
+    /// Confidence interval of [`Trend::ols_slope`].
+    pub ols_interval: (f64, f64),
+    /// The confidence level of the interval, between 0 and 1.
+    pub confidence: f64,
+    /// Median of the slopes between every pair of points.
+    pub sen_slope: f64,
+    pub mann_kendall: MannKendall,
+}
+
+/// The Mann-Kendall test of a monotonic trend, with the variance corrected for the ties.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MannKendall {
+    pub s: i64,
+    pub variance: f64,
+    pub z: f64,
+    /// Two-sided p-value, the trend is significant at 5% if it's below `0.05`.
+    pub p_value: f64,
+}
+
+impl Trend {
+    /// With a 95% confidence interval, see [`Trend::compute_with_confidence`].
+    pub fn compute(series: &[(f64, f64)]) -> Option<Self> {
+        Self::compute_with_confidence(series, 0.95)
+    }
+
+    /// `series` is a list of `(year, value)`, the year can be fractional.
+    /// `None` if there is less than 3 points or if all the points are on the same year.
+    pub fn compute_with_confidence(series: &[(f64, f64)], confidence: f64) -> Option<Self> {
+        let n = series.len();
+        if n < 3 {
+            return None;
+        }
+
+        let mean_x = series.iter().map(|(x, _)| x).sum::<f64>() / n as f64;
+        let mean_y = series.iter().map(|(_, y)| y).sum::<f64>() / n as f64;
+        let sxx: f64 = series.iter().map(|(x, _)| (x - mean_x).powi(2)).sum();
+        if sxx == 0.0 {
+            return None;
+        }
+        let sxy: f64 = series
+            .iter()
+            .map(|(x, y)| (x - mean_x) * (y - mean_y))
+            .sum();
+        let slope = sxy / sxx;
+        let intercept = mean_y - slope * mean_x;
+        let residuals: f64 = series
+            .iter()
+            .map(|(x, y)| (y - intercept - slope * x).powi(2))
+            .sum();
+        let standard_error = (residuals / (n - 2) as f64 / sxx).sqrt();
+        let t = student_t_quantile(0.5 + confidence / 2.0, (n - 2) as f64);
+
+        let mut slopes = Vec::new();
+        let mut s = 0;
+        for (i, (xi, yi)) in series.iter().enumerate() {
+            for (xj, yj) in &series[i + 1..] {
+                if xi != xj {
+                    slopes.push((yj - yi) / (xj - xi));
+                }
+                s += match (yj - yi) * (xj - xi).signum() {
+                    diff if diff > 0.0 => 1,
+                    diff if diff < 0.0 => -1,
+                    _ => 0,
+                };
+            }
+        }
+        slopes.sort_by(f64::total_cmp);
+        let sen_slope = match slopes.len() {
+            0 => 0.0,
+            len if len % 2 == 0 => (slopes[len / 2 - 1] + slopes[len / 2]) / 2.0,
+            len => slopes[len / 2],
+        };
+
+        Some(Self {
+            n,
+            ols_slope: slope * 10.0,
+            ols_intercept: intercept,
+            ols_interval: (
+                (slope - t * standard_error) * 10.0,
+                (slope + t * standard_error) * 10.0,
+            ),
+            confidence,
+            sen_slope: sen_slope * 10.0,
+            mann_kendall: MannKendall::new(s, series),
+        })
+    }
+
+    /// Compute the trend of a yearly series, like the output of [`Etccdi::annual`](crate::Etccdi::annual).
+    /// The missing years are ignored.
+    pub fn annual(series: &BTreeMap<i32, Option<f32>>) -> Option<Self> {
+        let series: Vec<(f64, f64)> = series
+            .iter()
+            .filter_map(|(year, value)| Some((*year as f64, (*value)? as f64)))
+            .collect();
+        Self::compute(&series)
+    }
+}
+
+impl MannKendall {
+    fn new(s: i64, series: &[(f64, f64)]) -> Self {
+        let n = series.len() as f64;
+        let mut values: Vec<f64> = series.iter().map(|(_, y)| *y).collect();
+        values.sort_by(f64::total_cmp);
+        let ties: f64 = values
+            .chunk_by(|left, right| left == right)
+            .map(|tie| tie.len() as f64)
+            .map(|t| t * (t - 1.0) * (2.0 * t + 5.0))
+            .sum();
+        let variance = (n * (n - 1.0) * (2.0 * n + 5.0) - ties) / 18.0;
+
+        let z = match s {
+            0 => 0.0,
+            s if variance == 0.0 => s.signum() as f64 * f64::INFINITY,
+            s if s > 0 => (s - 1) as f64 / variance.sqrt(),
+            s => (s + 1) as f64 / variance.sqrt(),
+        };
+
+        Self {
+            s,
+            variance,
+            z,
+            p_value: 2.0 * (1.0 - normal_cdf(z.abs())),
+        }
+    }
+}
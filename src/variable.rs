@@ -0,0 +1,165 @@
+use std::{fmt, ops::RangeInclusive, str::FromStr};
+
+use time::PrimitiveDateTime;
+
+use crate::{Day, Direction, RainUnit, TemperatureUnit, WindSpeedUnit};
+
+/// A measurement of a [`Day`], lets you select a field by its name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Variable {
+    MeanTemp,
+    HighTemp,
+    LowTemp,
+    HeatDegDays,
+    CoolDegDays,
+    Rain,
+    AvgWindSpeed,
+    HighWindSpeed,
+    /// The dominant wind direction in degrees, `0.0` being the north.
+    WindDirection,
+}
+
+/// How the daily values of a variable should be combined over a longer period.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Aggregation {
+    Sum,
+    Mean,
+    Max,
+    Min,
+    /// The most frequent value.
+    Mode,
+    /// The direction of the mean of the unit vectors, for angles in degrees.
+    VectorMean,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unit {
+    Temperature(TemperatureUnit),
+    Rain(RainUnit),
+    WindSpeed(WindSpeedUnit),
+    Degree,
+}
+
+impl Variable {
+    pub const ALL: [Variable; 9] = [
+        Variable::MeanTemp,
+        Variable::HighTemp,
+        Variable::LowTemp,
+        Variable::HeatDegDays,
+        Variable::CoolDegDays,
+        Variable::Rain,
+        Variable::AvgWindSpeed,
+        Variable::HighWindSpeed,
+        Variable::WindDirection,
+    ];
+
+    /// The name of the field in [`Day`].
+    pub fn name(&self) -> &'static str {
+        match self {
+            Variable::MeanTemp => "mean_temp",
+            Variable::HighTemp => "high_temp",
+            Variable::LowTemp => "low_temp",
+            Variable::HeatDegDays => "heat_deg_days",
+            Variable::CoolDegDays => "cool_deg_days",
+            Variable::Rain => "rain",
+            Variable::AvgWindSpeed => "avg_wind_speed",
+            Variable::HighWindSpeed => "high_wind_speed",
+            Variable::WindDirection => "wind_direction",
+        }
+    }
+
+    pub fn unit(&self) -> Unit {
+        match self {
+            Variable::MeanTemp
+            | Variable::HighTemp
+            | Variable::LowTemp
+            | Variable::HeatDegDays
+            | Variable::CoolDegDays => Unit::Temperature(TemperatureUnit::Celsius),
+            Variable::Rain => Unit::Rain(RainUnit::Mm),
+            Variable::AvgWindSpeed | Variable::HighWindSpeed => {
+                Unit::WindSpeed(WindSpeedUnit::KmHr)
+            }
+            Variable::WindDirection => Unit::Degree,
+        }
+    }
+
+    pub fn aggregation(&self) -> Aggregation {
+        match self {
+            Variable::MeanTemp | Variable::AvgWindSpeed => Aggregation::Mean,
+            Variable::HighTemp | Variable::HighWindSpeed => Aggregation::Max,
+            Variable::LowTemp => Aggregation::Min,
+            Variable::Rain | Variable::HeatDegDays | Variable::CoolDegDays => Aggregation::Sum,
+            Variable::WindDirection => Aggregation::Mode,
+        }
+    }
+
+    /// The values a sane station can report, anything outside is most probably a sensor error.
+    pub fn valid_range(&self) -> RangeInclusive<f32> {
+        match self {
+            Variable::MeanTemp | Variable::HighTemp | Variable::LowTemp => -60.0..=60.0,
+            Variable::HeatDegDays | Variable::CoolDegDays => 0.0..=80.0,
+            Variable::Rain => 0.0..=500.0,
+            Variable::AvgWindSpeed | Variable::HighWindSpeed => 0.0..=400.0,
+            Variable::WindDirection => 0.0..=360.0,
+        }
+    }
+
+    pub fn is_valid(&self, value: f32) -> bool {
+        self.valid_range().contains(&value)
+    }
+}
+
+impl fmt::Display for Variable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Variable {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Variable::ALL
+            .into_iter()
+            .find(|variable| variable.name() == s)
+            .ok_or_else(|| format!("Unknown variable: {s}"))
+    }
+}
+
+impl fmt::Display for Unit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Unit::Temperature(TemperatureUnit::Celsius) => f.write_str("ºC"),
+            Unit::Rain(RainUnit::Mm) => f.write_str("mm"),
+            Unit::WindSpeed(WindSpeedUnit::KmHr) => f.write_str("km/hr"),
+            Unit::Degree => f.write_str("º"),
+        }
+    }
+}
+
+impl Day {
+    /// `None` if the value is missing for this day.
+    pub fn get(&self, variable: Variable) -> Option<f32> {
+        match variable {
+            Variable::MeanTemp => Some(self.mean_temp),
+            Variable::HighTemp => Some(self.high_temp),
+            Variable::LowTemp => Some(self.low_temp),
+            Variable::HeatDegDays => Some(self.heat_deg_days),
+            Variable::CoolDegDays => Some(self.cool_deg_days),
+            Variable::Rain => Some(self.rain),
+            Variable::AvgWindSpeed => Some(self.avg_wind_speed),
+            Variable::HighWindSpeed => Some(self.high_wind_speed),
+            Variable::WindDirection => self.wind_direction.map(Direction::degrees),
+        }
+    }
+
+    /// When the value was reached, only the extremes are timestamped.
+    pub fn time_of(&self, variable: Variable) -> Option<PrimitiveDateTime> {
+        match variable {
+            Variable::HighTemp => Some(self.high_temp_date),
+            Variable::LowTemp => Some(self.low_temp_date),
+            Variable::HighWindSpeed => self.high_wind_speed_date,
+            _ => None,
+        }
+    }
+}